@@ -0,0 +1,43 @@
+//! Compares `lookup_path` with `lookup_path_bytes`.
+//!
+//! Run with `cargo bench --bench lookup`.
+use std::time::{Duration, Instant};
+use uclicious::*;
+
+const ITERATIONS: u32 = 1_000_000;
+
+fn measure<F: FnMut()>(mut f: F) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    start.elapsed()
+}
+
+fn main() {
+    let mut parser = Parser::default();
+    let input = r#"
+        server {
+            listen {
+                port = 8080
+            }
+        }
+    "#;
+    parser
+        .add_chunk_full(input, Priority::default(), DEFAULT_DUPLICATE_STRATEGY)
+        .unwrap();
+    let root = parser.get_object().unwrap();
+
+    let with_alloc = measure(|| {
+        std::hint::black_box(root.lookup_path(std::hint::black_box("server.listen.port")));
+    });
+    let without_alloc = measure(|| {
+        std::hint::black_box(root.lookup_path_bytes(std::hint::black_box(b"server.listen.port\0")));
+    });
+
+    println!("lookup_path:       {:?} per call", with_alloc / ITERATIONS);
+    println!(
+        "lookup_path_bytes: {:?} per call",
+        without_alloc / ITERATIONS
+    );
+}
@@ -1,4 +1,3 @@
-use uclicious;
 use uclicious::raw::*;
 
 fn main() {
@@ -10,10 +9,11 @@ fn main() {
         .unwrap();
 
     let result = parser.get_object().unwrap();
-    let lookup_result = result.lookup("game").unwrap();
-    assert_eq!(true, lookup_result.as_bool().unwrap());
-    drop(lookup_result);
+    {
+        let lookup_result = result.lookup("game").unwrap();
+        assert!(lookup_result.as_bool().unwrap());
+    }
 
     let lookup_result = result.lookup("game").unwrap();
-    assert_eq!(true, lookup_result.as_bool().unwrap());
+    assert!(lookup_result.as_bool().unwrap());
 }
@@ -21,43 +21,38 @@ pub enum UclErrorType {
 
 impl UclErrorType {
     pub fn from_code(num: i32, desc: String) -> UclError {
-        match num {
-            _ if num == ucl_error_t::UCL_EOK as i32 => UclError {
-                code: UclErrorType::Ok,
-                desc,
-            },
-            _ if num == ucl_error_t::UCL_ESYNTAX as i32 => UclError {
-                code: UclErrorType::Syntax,
-                desc,
-            },
-            _ if num == ucl_error_t::UCL_EIO as i32 => UclError {
-                code: UclErrorType::Io,
-                desc,
-            },
-            _ if num == ucl_error_t::UCL_ESTATE as i32 => UclError {
-                code: UclErrorType::State,
-                desc,
-            },
-            _ if num == ucl_error_t::UCL_ENESTED as i32 => UclError {
-                code: UclErrorType::Nested,
-                desc,
-            },
-            _ if num == ucl_error_t::UCL_EMACRO as i32 => UclError {
-                code: UclErrorType::Macro,
-                desc,
-            },
-            _ if num == ucl_error_t::UCL_EINTERNAL as i32 => UclError {
-                code: UclErrorType::Internal,
-                desc,
-            },
-            _ if num == ucl_error_t::UCL_ESSL as i32 => UclError {
-                code: UclErrorType::SSL,
-                desc,
-            },
-            _ => UclError {
-                code: UclErrorType::Other,
-                desc,
-            },
+        let code = match num {
+            _ if num == ucl_error_t::UCL_EOK as i32 => UclErrorType::Ok,
+            _ if num == ucl_error_t::UCL_ESYNTAX as i32 => UclErrorType::Syntax,
+            _ if num == ucl_error_t::UCL_EIO as i32 => UclErrorType::Io,
+            _ if num == ucl_error_t::UCL_ESTATE as i32 => UclErrorType::State,
+            _ if num == ucl_error_t::UCL_ENESTED as i32 => UclErrorType::Nested,
+            _ if num == ucl_error_t::UCL_EMACRO as i32 => UclErrorType::Macro,
+            _ if num == ucl_error_t::UCL_EINTERNAL as i32 => UclErrorType::Internal,
+            _ if num == ucl_error_t::UCL_ESSL as i32 => UclErrorType::SSL,
+            _ => UclErrorType::Other,
+        };
+        UclError {
+            code,
+            raw: num,
+            desc,
+            line: None,
+            column: None,
+        }
+    }
+
+    /// libUCL code for this kind. There is no code for `Other`, so `-1` is used.
+    fn as_code(self) -> i32 {
+        match self {
+            UclErrorType::Ok => ucl_error_t::UCL_EOK as i32,
+            UclErrorType::Syntax => ucl_error_t::UCL_ESYNTAX as i32,
+            UclErrorType::Io => ucl_error_t::UCL_EIO as i32,
+            UclErrorType::State => ucl_error_t::UCL_ESTATE as i32,
+            UclErrorType::Nested => ucl_error_t::UCL_ENESTED as i32,
+            UclErrorType::Macro => ucl_error_t::UCL_EMACRO as i32,
+            UclErrorType::Internal => ucl_error_t::UCL_EINTERNAL as i32,
+            UclErrorType::SSL => ucl_error_t::UCL_ESSL as i32,
+            UclErrorType::Other => -1,
         }
     }
 }
@@ -65,12 +60,21 @@ impl UclErrorType {
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct UclError {
     code: UclErrorType,
+    raw: i32,
     desc: String,
+    line: Option<usize>,
+    column: Option<usize>,
 }
 
 impl fmt::Display for UclError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.desc)
+        write!(f, "{}", self.desc)?;
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => write!(f, " at line {}, column {}", line, column),
+            (Some(line), None) => write!(f, " at line {}", line),
+            (None, Some(column)) => write!(f, " at column {}", column),
+            (None, None) => Ok(()),
+        }
     }
 }
 
@@ -85,6 +89,17 @@ impl Error for UclError {
 }
 
 impl UclError {
+    /// Create a new error of the given kind with a description.
+    pub fn new<D: Into<String>>(code: UclErrorType, desc: D) -> UclError {
+        UclError {
+            code,
+            raw: code.as_code(),
+            desc: desc.into(),
+            line: None,
+            column: None,
+        }
+    }
+
     pub fn boxed(self) -> Box<UclError> {
         Box::new(self)
     }
@@ -92,6 +107,38 @@ impl UclError {
     pub fn kind(&self) -> UclErrorType {
         self.code
     }
+
+    /// Numeric code this error was created from. Useful to tell apart errors of `Other` kind.
+    pub fn code_raw(&self) -> i32 {
+        self.raw
+    }
+
+    /// Line of the source where parser stopped, if known.
+    pub fn line(&self) -> Option<usize> {
+        self.line
+    }
+
+    /// Column of the source where parser stopped, if known.
+    pub fn column(&self) -> Option<usize> {
+        self.column
+    }
+
+    pub(crate) fn with_position(mut self, line: Option<usize>, column: Option<usize>) -> Self {
+        self.line = line;
+        self.column = column;
+        self
+    }
+}
+
+impl From<UclError> for std::io::Error {
+    /// Syntax errors become `ErrorKind::InvalidData`, everything else is `ErrorKind::Other`.
+    fn from(err: UclError) -> Self {
+        let kind = match err.kind() {
+            UclErrorType::Syntax => std::io::ErrorKind::InvalidData,
+            _ => std::io::ErrorKind::Other,
+        };
+        std::io::Error::new(kind, err)
+    }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -165,3 +212,42 @@ impl Error for UclSchemaError {
         None
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn code_raw() {
+        let err = UclErrorType::from_code(ucl_error_t::UCL_ESYNTAX as i32, "syntax".to_string());
+        assert_eq!(UclErrorType::Syntax, err.kind());
+        assert_eq!(ucl_error_t::UCL_ESYNTAX as i32, err.code_raw());
+
+        let err = UclErrorType::from_code(42, "unknown".to_string());
+        assert_eq!(UclErrorType::Other, err.kind());
+        assert_eq!(42, err.code_raw());
+
+        let err = UclError::new(UclErrorType::Io, "io");
+        assert_eq!(ucl_error_t::UCL_EIO as i32, err.code_raw());
+    }
+
+    #[test]
+    fn into_io_error() {
+        let err: std::io::Error = UclError::new(UclErrorType::Syntax, "unexpected }").into();
+        assert_eq!(std::io::ErrorKind::InvalidData, err.kind());
+        assert_eq!("unexpected }", err.to_string());
+
+        let err: std::io::Error = UclError::new(UclErrorType::Nested, "too deep").into();
+        assert_eq!(std::io::ErrorKind::Other, err.kind());
+    }
+
+    #[test]
+    fn display_position() {
+        let err = UclError::new(UclErrorType::Syntax, "unexpected }");
+        assert_eq!("unexpected }", err.to_string());
+        let err = err.with_position(Some(3), Some(7));
+        assert_eq!("unexpected } at line 3, column 7", err.to_string());
+        let err = err.with_position(Some(3), None);
+        assert_eq!("unexpected } at line 3", err.to_string());
+    }
+}
@@ -141,6 +141,10 @@
 //! ```
 //!
 //! If you choose to derive builder then `::builder()` method will be added to target struct.
+//! It also gets `::from_object(&ObjectRef)` method that converts an already parsed object, e.g. a sub-object of a bigger config, without running the parser.
+//! Checks set with `build_fn(validate = ..)` and deprecation checks are part of `build()`, so they don't run there.
+//!
+//! Builder's `build()` consumes it, `build_ref()` borrows it instead, so more sources can be added and the struct built again.
 //!
 //! #### Validators
 //!
@@ -260,17 +264,77 @@
 //!     mode: Mode::On
 //! };
 //! ```
+//! #### Enums
+//!
+//! Fieldless enums can be derived as well. Value is read as a string and matched against variant names:
+//!
+//! ```rust
+//! use uclicious::*;
+//!
+//! #[derive(Debug, Eq, PartialEq, Uclicious)]
+//! #[ucl(rename_all = "lowercase")]
+//! enum Mode {
+//!     On,
+//!     Off,
+//!     #[ucl(rename = "auto-detect")]
+//!     Auto,
+//! }
+//!
+//! #[derive(Debug, Uclicious)]
+//! struct Mapped {
+//!     mode: Mode,
+//!     fallback: Mode,
+//! }
+//! let mut builder = Mapped::builder().unwrap();
+//!
+//! let input = r#"
+//!     mode = "auto-detect"
+//!     fallback = "off"
+//! "#;
+//! builder.add_chunk_full(input, Priority::default(), DEFAULT_DUPLICATE_STRATEGY).unwrap();
+//! let actual = builder.build().unwrap();
+//! assert_eq!(Mode::Auto, actual.mode);
+//! assert_eq!(Mode::Off, actual.fallback);
+//! ```
 //! ### Supported attributes (`#[ucl(..)]`)
 //!
 //! #### Structure level
 //!
 //!  - `skip_builder`
 //!     - if set, then builder and builder methods won't be generated.
+//!  - `clonable_builder`
+//!     - if set, then builder implements `Clone`.
+//!     - Clone gets a fresh parser with a deep copy of everything parsed so far, added with `Parser::add_object`, so it's not free.
+//!     - Variables, handlers and macros added to the builder after it was created are not carried over, use attributes or `pre_source_hook` instead.
+//!  - `programmatic_setters`
+//!     - if set, then builder has `with_<field>(self, value) -> Result<Self, UclError>` method for every field that is looked up by a key.
+//!     - Value is converted with `Into<Object>` and replaces the key in the parsed object with the highest priority, so chunks added later don't override it. Calling a setter again replaces the value.
+//!     - Fields whose type doesn't implement `Into<Object>` need `skip_setter`.
+//!  - `track_presence`
+//!     - if set, then builder has `build_with_sources(self) -> Result<(T, HashMap<&'static str, FieldSource>), Box<dyn Error>>` method.
+//!     - Reports for every field that is looked up by a key whether it was set by sources (`FieldSource::Config`), by a file included with `env_path` (`FieldSource::Env`) or got its default value (`FieldSource::Default`).
+//!  - `getters`
+//!     - if set, then target struct has `pub fn <field>(&self) -> &FieldType` method for every field, so fields can stay private.
+//!     - Getters are named after fields, even when `path` sets a different key.
+//!  - `rename_all = string`
+//!     - Rule to convert field names into keys, when field doesn't have `path`.
+//!     - One of `lowercase`, `UPPERCASE`, `snake_case`, `kebab-case`, `camelCase`, `PascalCase` or `SCREAMING_SNAKE_CASE`.
+//!     - Field names are used as is by default.
+//!  - `path_sep = string`
+//!     - Separator of segments in field paths, `.` by default.
+//!     - Needed when keys have dots in them, e.g. `path_sep = "/"` and `path = "domains/example.com/port"`.
+//!     - Must be a single ASCII character.
+//!  - `build_fn(validate = path::to_method)`
+//!     - Run a function on the built struct before `build()` returns it, e.g. to check fields against each other.
+//!     - `Fn(value: &T) -> Result<(), E>`
+//!     - Error needs to be convertable into `Box<dyn Error + Send + Sync>`
 //!  - `parser(..)`
 //!     - Optional attribute to configure inner parser.
 //!     - Has following nested attributes:
 //!         - `flags`
 //!             - a path to function that returns flags.
+//!             - or a list of flags, combined with `|`, e.g. `flags(key_lowercase, no_time)`.
+//!             - One of `key_lowercase`, `no_time`, `no_implicit_arrays`, `save_comments`, `disable_macro` or `no_filevars`.
 //!         - `filevars(..)`
 //!             - call `set_filevars` on a parser.
 //!             - Has following nested attributes:
@@ -278,10 +342,18 @@
 //!                     - a string representation of filepath.
 //!                 - `expand`
 //!                     - (optional) if set, then variables would be expanded to absolute.
-//!  - `pre_source_hook(...)`
+//!         - `register_macro(..)`
+//!             - call `register_macro` on a parser, can be used multiple times.
+//!             - Has following nested attributes:
+//!                 - `name`
+//!                     - a name of the macro without leading `.`.
+//!                 - `handler`
+//!                     - a path to function that returns `impl MacroHandler`.
+//!  - `pre_source_hook = path::to_function`
 //!     - Optional attribute to run a function before sources are added
+//!     - Called by `new()` after variables from `var(..)` are registered and before `include(..)` sources are added
 //!     - Can be used to register vars handler
-//!     - Must take `&mut Parser` as argument and return `Result<(), Into<UclError>>`
+//!     - `Fn(&mut Parser) -> Result<(), E>`, where `E` is convertable into `UclError`
 //!  - `var(..)`
 //!     - Optional attribute to register string variables with the parser.
 //!     - Has following nested attributes:
@@ -293,7 +365,7 @@
 //!  - `include(..)`
 //!     - Used to add files into the parser.
 //!     - If file doesn't exist or failed to parse, then error will be returned in a constructor.
-//!     - Must specify exactly one of following sources: `path`, `chunk` or `chunk_static`
+//!     - Must specify exactly one of following sources: `path`, `chunk`, `chunk_static` or `env_path`
 //!     - Has following nested attirbutes:
 //!         - (semi-optional) `path = string`
 //!             - File path. Can be absolute or relative to CWD.
@@ -301,6 +373,10 @@
 //!             - A string that will be added to parser as a chunk.
 //!         - (semi-optional) `chunk_static = string`
 //!             - A path to a file that will be included into binary with [`include_str!()`](https://doc.rust-lang.org/std/macro.include_str.html)
+//!         - (semi-optional) `env_path = string`
+//!             - Name of an environment variable that holds the file path. Resolved when builder is created, a missing variable is returned as an error.
+//!         - (optional) `suffix = string`
+//!             - Appended to the value of `env_path`, e.g. `env_path = "HOME", suffix = "/.myapp.conf"`.
 //!         - (optional) `priority = u32`
 //!             - 0-15 priority for the source. Consult the libUCL documentation for more information.
 //!         - (optional) `strategy = uclicious::DuplicateStrategy`
@@ -314,25 +390,68 @@
 //!  - `default = expression`
 //!     - Use this _expression_ as value if key not found.
 //!     - Could be a value or a function call.
+//!  - `default_fn = path::to_function`
+//!     - `Fn() -> Result<T, ObjectError>`, called for a value if key not found.
+//!     - Error is returned from `build()`. Can't be combined with `default`.
 //!  - `path = string`
 //!     - By default field name is used as path.
 //!     - If set that would be used as a key.
 //!     - dot notation for key is supported.
+//!     - Numeric segments index into arrays, e.g. `servers.0.host`. Keys that became implicit arrays can't be indexed.
 //!  - `validate = path::to_method`
 //!     - `Fn(key: &str, value: &T) -> Result<(), E>`
-//!     - Error needs to be convertable into `ObjectError`
+//!     - `E` can be any `Display + 'static` error. `ObjectError` is returned as is, other errors are converted with `ObjectError::other`
 //!  - `from = Type`
 //!     - Try to convert `ObjectRef` to `Type` and then use `std::convert::From` to convert into target type
 //!  - `try_from = Type`
 //!     - Try to convert `ObjectRef` to `Type` and then use `std::convert::TryFrom` to convert into target type
 //!     - Error will be converted into `ObjectError::Other`
+//!  - `parse_with = path::to_function`
+//!     - `Fn(&str) -> Result<T, E>`, where `E: Display`
+//!     - Same as `from_str`, but calls this function instead of `std::str::FromStr`, e.g. to parse hex numbers
+//!     - Error will be converted into `ObjectError::Other`
 //!  - `map = path::to_method`
 //!     - `Fn(src: ObjectRef) -> Result<T, E>`
 //!     - A way to map foreign objects that can't implement `From` or `TryFrom` or when error is not convertable into `ObjectError`
+//!  - `flags_from = path::to_method`
+//!     - `Fn(&str) -> Option<T>`, where `T` is a flag set (e.g. made with `bitflags`) that implements `Default` and `BitOr`
+//!     - Value must be an array of strings, flags of every element are combined with `|`, starting from `T::default()`
+//!     - Unknown flag will be converted into `ObjectError::Other`
+//!  - `split = string`
+//!     - If value is a single string, split it by the delimiter into the field (e.g. `Vec<String>`), trimming every element and skipping empty ones
+//!     - Any other value is converted as usual, so a real array works too
+//!  - `flatten`
+//!     - Read field from the same object as the parent struct, e.g. to split keys into nested structs that also derive `Uclicious`.
+//!     - Field type must implement `FromObject`, use `rest` to collect keys into a map.
+//!     - Can't be combined with `path`, `rest`, `from`, `try_from`, `map` or `from_str`.
+//!  - `rest`
+//!     - Collect all keys on the same level that are not used by other fields into a map, e.g. `HashMap<String, T>` or a type alias of it.
+//!     - Keys used by fields with dotted `path` are excluded by their first segment. Keys of flattened structs are not excluded.
+//!  - `deprecated = string`
+//!     - Report this message when key is present in the config.
+//!     - Notices are printed to stderr, use `set_deprecation_sink` on the builder to handle them differently.
+//!     - Only the builder checks for deprecated keys.
+//!  - `skip`
+//!     - Don't look up this field at all, always use default value.
+//!     - Combine with `default` or `default = expression`, otherwise `Default::default()` is used.
+//!  - `float_from_int`
+//!     - Shorthand for `from = "f64"`: `f64` accepts integers as well, so this reads both into a type with `From<f64>`, e.g. a newtype.
+//!     - Combines with other attributes the same way as `from`.
+//!  - `skip_setter`
+//!     - Don't generate `with_<field>` setter for this field when `programmatic_setters` is set.
+//!
+//! #### Enum level
+//!
+//!  - `rename_all = string`
+//!     - Rule to convert variant names, when variant doesn't have `rename`.
+//!     - One of `lowercase`, `UPPERCASE`, `snake_case`, `kebab-case`, `camelCase`, `PascalCase` or `SCREAMING_SNAKE_CASE`.
+//!     - Variant names are used as is by default.
+//!  - `rename = string` on a variant
+//!     - Use this string instead of variant name.
 //!
 //! ### Additional notes
 //!  - If target type is an array, but key is a single value — an implicit list is created.
-//!  - Automatic derive on enums is not supported, but you can implement it yourself.
+//!  - Automatic derive on enums is supported only for fieldless enums.
 //!  - I have a few more features I want to implement before publishing this crate:
 //!     - Ability to add variables.
 //!     - Ability to add macross handlers.
@@ -357,7 +476,7 @@
 //!  - Automatic derive, so you don't have to think about parser object
 //!
 //! ### Not Goals
-//!  - Providing UCL Object generation tools is not a goal for this project
+//!  - Providing UCL Object generation tools is not a goal for this project (emitting parsed objects as JSON for debugging and building small fragments to merge into parsed config are supported)
 //!  - 1:1 interface to libUCL
 //!  - sugar inside `raw` module
 //!
@@ -374,12 +493,14 @@
 //! [BSD-2-Clause](https://github.com/andoriyu/uclicious/blob/master/LICENSE).
 pub mod error;
 pub mod raw;
+#[cfg(feature = "testing")]
+pub mod testing;
 pub mod traits;
 pub mod variable_handlers;
 
-pub use error::{UclError, UclErrorType};
+pub use error::{UclError, UclErrorType, UclSchemaError, UclSchemaErrorType};
 pub use raw::{
-    DuplicateStrategy, Object, ObjectError, ObjectRef, Parser, ParserFlags, Priority,
+    ByteSize, DuplicateStrategy, Object, ObjectError, ObjectRef, Parser, ParserFlags, Priority,
     DEFAULT_DUPLICATE_STRATEGY, DEFAULT_PARSER_FLAG,
 };
 pub use traits::{FromObject, TryInto};
@@ -396,7 +517,7 @@ pub use uclicious_derive::*;
 #[cfg(test)]
 mod test {
     use super::*;
-    use std::collections::HashMap;
+    use std::collections::{BTreeSet, HashMap, HashSet};
 
     #[test]
     fn primitives_from_object() {
@@ -409,7 +530,7 @@ mod test {
             u32 = 1
             u16 = 1
             u8  = 1
-            f64 = 3.14
+            f64 = 1.25
             bool = true
         "#;
 
@@ -420,10 +541,10 @@ mod test {
         let root = parser.get_object().unwrap();
 
         let boolean: bool = FromObject::try_from(root.lookup("bool").unwrap()).unwrap();
-        assert_eq!(true, boolean);
+        assert!(boolean);
 
         let float64: f64 = FromObject::try_from(root.lookup("f64").unwrap()).unwrap();
-        assert_eq!(3.14f64, float64);
+        assert_eq!(1.25f64, float64);
 
         let int64: i64 = FromObject::try_from(root.lookup("i64").unwrap()).unwrap();
         assert_eq!(1, int64);
@@ -506,6 +627,72 @@ mod test {
         assert!(actual.is_err());
     }
 
+    #[test]
+    fn set_from_object() {
+        let input = r#"
+            list = [3,1,2,1]
+            implicit_list = 1
+            bad_list = [1, "two"]
+        "#;
+
+        let mut parser = Parser::default();
+        parser
+            .add_chunk_full(input, Priority::default(), DEFAULT_DUPLICATE_STRATEGY)
+            .unwrap();
+        let root = parser.get_object().unwrap();
+
+        let expected: HashSet<i32> = vec![1, 2, 3].into_iter().collect();
+        let actual: HashSet<i32> = FromObject::try_from(root.lookup("list").unwrap()).unwrap();
+        assert_eq!(expected, actual);
+
+        let expected = vec![1, 2, 3];
+        let actual: BTreeSet<i32> = FromObject::try_from(root.lookup("list").unwrap()).unwrap();
+        assert_eq!(expected, actual.into_iter().collect::<Vec<_>>());
+
+        let expected: BTreeSet<i32> = vec![1].into_iter().collect();
+        let actual: BTreeSet<i32> =
+            FromObject::try_from(root.lookup("implicit_list").unwrap()).unwrap();
+        assert_eq!(expected, actual);
+
+        let actual: Result<HashSet<i32>, ObjectError> =
+            FromObject::try_from(root.lookup("bad_list").unwrap());
+        assert!(actual.is_err());
+    }
+
+    #[test]
+    fn tuple_from_object() {
+        let input = r#"
+            range = [0,100]
+            point = [1.0, 2.0, 3.0]
+            mixed = [1, "two", yes, 4.0]
+            not_an_array = 1
+        "#;
+
+        let mut parser = Parser::default();
+        parser
+            .add_chunk_full(input, Priority::default(), DEFAULT_DUPLICATE_STRATEGY)
+            .unwrap();
+        let root = parser.get_object().unwrap();
+
+        let actual: (i64, i64) = FromObject::try_from(root.lookup("range").unwrap()).unwrap();
+        assert_eq!((0, 100), actual);
+
+        let actual: (f64, f64, f64) = FromObject::try_from(root.lookup("point").unwrap()).unwrap();
+        assert_eq!((1.0, 2.0, 3.0), actual);
+
+        let actual: (i32, String, bool, f64) =
+            FromObject::try_from(root.lookup("mixed").unwrap()).unwrap();
+        assert_eq!((1, String::from("two"), true, 4.0), actual);
+
+        let actual: Result<(i64, i64, i64), ObjectError> =
+            FromObject::try_from(root.lookup("range").unwrap());
+        assert!(matches!(actual, Err(ObjectError::Other(_))));
+
+        let actual: Result<(i64, i64), ObjectError> =
+            FromObject::try_from(root.lookup("not_an_array").unwrap());
+        assert!(matches!(actual, Err(ObjectError::WrongType { .. })));
+    }
+
     #[test]
     fn hashmap_from_object() {
         let input = r#"
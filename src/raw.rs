@@ -1,13 +1,22 @@
 //! Low level interface to libUCL.
 
+#[cfg(feature = "serde")]
+mod de;
+pub mod diff;
 pub mod iterator;
+#[cfg(feature = "serde")]
+mod json;
 pub mod object;
 pub mod parser;
 pub mod priority;
+#[cfg(feature = "serde")]
+mod ser;
 mod utils;
 
-pub use object::{Object, ObjectError, ObjectRef};
-pub use parser::Parser;
+#[cfg(feature = "serde")]
+pub use de::ObjectDeserializer;
+pub use object::{ByteSize, ErrorSource, Object, ObjectError, ObjectRef};
+pub use parser::{Parser, SendParser};
 pub use priority::Priority;
 
 /// Strategy to use when sources have duplicate keys.
@@ -15,10 +24,31 @@ pub type DuplicateStrategy = libucl_bind::ucl_duplicate_strategy;
 /// Default strategy is append. Consult libUCL docs for more information.
 pub const DEFAULT_DUPLICATE_STRATEGY: DuplicateStrategy = DuplicateStrategy::UCL_DUPLICATE_APPEND;
 
-/// Parser flags.
-pub type ParserFlags = libucl_bind::ucl_parser_flags;
+/// Raw libUCL object, as passed to macro handlers.
+pub type RawObject = libucl_bind::ucl_object_t;
+
+bitflags::bitflags! {
+    /// Parser flags, combined with `|`.
+    #[derive(Default)]
+    pub struct ParserFlags: u32 {
+        /// No special flags.
+        const DEFAULT = libucl_bind::ucl_parser_flags::UCL_PARSER_DEFAULT.0;
+        /// Convert all keys to lower case.
+        const KEY_LOWERCASE = libucl_bind::ucl_parser_flags::UCL_PARSER_KEY_LOWERCASE.0;
+        /// Do not parse time and treat time values as strings.
+        const NO_TIME = libucl_bind::ucl_parser_flags::UCL_PARSER_NO_TIME.0;
+        /// Create explicit arrays instead of implicit ones for duplicate keys.
+        const NO_IMPLICIT_ARRAYS = libucl_bind::ucl_parser_flags::UCL_PARSER_NO_IMPLICIT_ARRAYS.0;
+        /// Save comments in the parser context.
+        const SAVE_COMMENTS = libucl_bind::ucl_parser_flags::UCL_PARSER_SAVE_COMMENTS.0;
+        /// Treat macros as comments.
+        const DISABLE_MACRO = libucl_bind::ucl_parser_flags::UCL_PARSER_DISABLE_MACRO.0;
+        /// Do not set file variables `$FILENAME` and `$CURDIR`.
+        const NO_FILEVARS = libucl_bind::ucl_parser_flags::UCL_PARSER_NO_FILEVARS.0;
+    }
+}
 #[doc(hidden)]
-pub const DEFAULT_PARSER_FLAG: ParserFlags = ParserFlags::UCL_PARSER_DEFAULT;
+pub const DEFAULT_PARSER_FLAG: ParserFlags = ParserFlags::DEFAULT;
 
 #[cfg(test)]
 mod test {
@@ -60,16 +90,16 @@ mod test {
     #[test]
     fn float_parsing() {
         let mut parser = Parser::default();
-        let input = r#"pi = 3.14"#;
+        let input = r#"float = 1.25"#;
 
         parser
             .add_chunk_full(input, Priority::default(), DEFAULT_DUPLICATE_STRATEGY)
             .unwrap();
 
         let result = parser.get_object().unwrap();
-        let lookup_result = result.lookup("pi").unwrap().as_f64().unwrap();
+        let lookup_result = result.lookup("float").unwrap().as_f64().unwrap();
 
-        assert_eq!(3.14, lookup_result);
+        assert_eq!(1.25, lookup_result);
     }
 
     #[test]
@@ -84,7 +114,7 @@ mod test {
         let result = parser.get_object().unwrap();
         let lookup_result = result.lookup("game").unwrap().as_bool().unwrap();
 
-        assert_eq!(true, lookup_result);
+        assert!(lookup_result);
     }
 
     #[test]
@@ -146,12 +176,13 @@ mod test {
             .unwrap();
 
         let result = parser.get_object().unwrap();
-        let lookup_result = result.lookup("game").unwrap();
-        assert_eq!(true, lookup_result.as_bool().unwrap());
-        drop(lookup_result);
+        {
+            let lookup_result = result.lookup("game").unwrap();
+            assert!(lookup_result.as_bool().unwrap());
+        }
 
         let lookup_result = result.lookup("game").unwrap();
-        assert_eq!(true, lookup_result.as_bool().unwrap());
+        assert!(lookup_result.as_bool().unwrap());
     }
 
     #[test]
@@ -176,15 +207,15 @@ mod test {
     fn object_from_primitive() {
         let obj_boolean = Object::from(false);
         assert_eq!(ucl_type_t::UCL_BOOLEAN, obj_boolean.kind());
-        assert_eq!(false, obj_boolean.as_bool().unwrap());
+        assert!(!obj_boolean.as_bool().unwrap());
 
         let obj_i64 = Object::from(1776i64);
         assert_eq!(ucl_type_t::UCL_INT, obj_i64.kind());
         assert_eq!(1776, obj_i64.as_i64().unwrap());
 
-        let obj_f64 = Object::from(3.14);
+        let obj_f64 = Object::from(1.25);
         assert_eq!(ucl_type_t::UCL_FLOAT, obj_f64.kind());
-        assert_eq!(3.14, obj_f64.as_f64().unwrap());
+        assert_eq!(1.25, obj_f64.as_f64().unwrap());
 
         let obj_str = Object::from("a string without null");
         assert_eq!(ucl_type_t::UCL_STRING, obj_str.kind());
@@ -198,4 +229,21 @@ mod test {
 
         assert!(err.to_string().contains("os error 42"));
     }
+
+    #[test]
+    fn dyn_error_with_source() {
+        use std::error::Error;
+
+        let err = std::io::Error::from_raw_os_error(42);
+        let err = ObjectError::other_with_source("failed to read", err);
+
+        assert_eq!("failed to read", err.to_string());
+        let source = err.source().unwrap();
+        assert!(source.to_string().contains("os error 42"));
+        let io_err = source.downcast_ref::<std::io::Error>().unwrap();
+        assert_eq!(Some(42), io_err.raw_os_error());
+
+        let cloned = err.clone();
+        assert_eq!(err, cloned);
+    }
 }
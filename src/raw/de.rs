@@ -0,0 +1,272 @@
+//! `serde::Deserializer` over objects, so types that derive `serde::Deserialize` can be read from UCL.
+//!
+//! Keys with more than one value (implicit arrays) are deserialized as sequences, a single value can be read into a sequence as well.
+use crate::raw::object::{ObjectError, ObjectRef};
+use libucl_bind::ucl_type_t;
+use serde::de::value::{MapAccessDeserializer, SeqDeserializer, StringDeserializer};
+use serde::de::{
+    DeserializeOwned, DeserializeSeed, Deserializer, Error, IntoDeserializer, MapAccess, Visitor,
+};
+use std::vec::IntoIter;
+
+impl ObjectRef {
+    /// Deserialize this object into any type that implements `serde::Deserialize`.
+    pub fn deserialize_into<T: DeserializeOwned>(&self) -> Result<T, ObjectError> {
+        T::deserialize(ObjectDeserializer::new(self))
+    }
+}
+
+impl Error for ObjectError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        ObjectError::other(msg)
+    }
+}
+
+/// Deserializer over an object, e.g. `MyStruct::deserialize(ObjectDeserializer::new(&obj))`.
+pub struct ObjectDeserializer(ObjectRef);
+
+impl ObjectDeserializer {
+    /// Create a deserializer for given object.
+    pub fn new(obj: &ObjectRef) -> Self {
+        ObjectDeserializer(obj.entry().1)
+    }
+
+    /// Deserializer for all values of a key: the value itself if there is only one, a sequence otherwise.
+    fn deserialize_values<'de, S: DeserializeSeed<'de>>(
+        mut values: Vec<ObjectRef>,
+        seed: S,
+    ) -> Result<S::Value, ObjectError> {
+        if values.len() == 1 {
+            seed.deserialize(ObjectDeserializer(values.remove(0)))
+        } else {
+            seed.deserialize(SeqDeserializer::new(
+                values.into_iter().map(ObjectDeserializer),
+            ))
+        }
+    }
+
+    fn visit_seq<'de, V: Visitor<'de>>(
+        values: Vec<ObjectRef>,
+        visitor: V,
+    ) -> Result<V::Value, ObjectError> {
+        let mut seq = SeqDeserializer::new(values.into_iter().map(ObjectDeserializer));
+        let value = visitor.visit_seq(&mut seq)?;
+        seq.end()?;
+        Ok(value)
+    }
+
+    fn map_access(&self) -> ObjectMapAccess {
+        ObjectMapAccess {
+            children: self.0.iterate_raw(true).into_iter(),
+            values: None,
+        }
+    }
+}
+
+impl<'de> IntoDeserializer<'de, ObjectError> for ObjectDeserializer {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+impl<'de> Deserializer<'de> for ObjectDeserializer {
+    type Error = ObjectError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let obj = &self.0;
+        match obj.kind() {
+            ucl_type_t::UCL_OBJECT => visitor.visit_map(self.map_access()),
+            ucl_type_t::UCL_ARRAY => Self::visit_seq(obj.iterate_raw(true), visitor),
+            ucl_type_t::UCL_INT => visitor.visit_i64(obj.as_i64().unwrap_or_default()),
+            ucl_type_t::UCL_FLOAT | ucl_type_t::UCL_TIME => {
+                visitor.visit_f64(obj.as_f64().unwrap_or_default())
+            }
+            ucl_type_t::UCL_STRING => visitor.visit_string(obj.as_string().unwrap_or_default()),
+            ucl_type_t::UCL_BOOLEAN => visitor.visit_bool(obj.as_bool().unwrap_or_default()),
+            ucl_type_t::UCL_USERDATA | ucl_type_t::UCL_NULL => visitor.visit_unit(),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        if self.0.is_null() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        if self.0.is_array() {
+            self.deserialize_any(visitor)
+        } else {
+            Self::visit_seq(vec![self.0], visitor)
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        match self.0.kind() {
+            ucl_type_t::UCL_STRING => {
+                let variant: StringDeserializer<ObjectError> =
+                    self.0.as_string().unwrap_or_default().into_deserializer();
+                visitor.visit_enum(variant)
+            }
+            ucl_type_t::UCL_OBJECT => {
+                visitor.visit_enum(MapAccessDeserializer::new(self.map_access()))
+            }
+            _ => Err(ObjectError::other(format!(
+                "expected a string or an object for enum `{}`",
+                self.0.key().unwrap_or_default()
+            ))),
+        }
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct tuple tuple_struct map struct identifier
+    }
+}
+
+struct ObjectMapAccess {
+    children: IntoIter<ObjectRef>,
+    values: Option<Vec<ObjectRef>>,
+}
+
+impl<'de> MapAccess<'de> for ObjectMapAccess {
+    type Error = ObjectError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        match self.children.next() {
+            Some(child) => {
+                let key: StringDeserializer<ObjectError> =
+                    child.key().unwrap_or_default().into_deserializer();
+                self.values = Some(child.iterate_raw(false));
+                seed.deserialize(key).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        let values = self
+            .values
+            .take()
+            .ok_or_else(|| ObjectError::other("value requested before key"))?;
+        ObjectDeserializer::deserialize_values(values, seed)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Parser, Priority, DEFAULT_DUPLICATE_STRATEGY};
+    use serde::Deserialize;
+    use std::collections::HashMap;
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    #[serde(rename_all = "lowercase")]
+    enum Mode {
+        Fast,
+        Safe,
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Upstream {
+        hosts: Vec<String>,
+        timeout: f64,
+        weight: Option<u8>,
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Config {
+        name: String,
+        port: u16,
+        enabled: bool,
+        mode: Mode,
+        upstream: Upstream,
+        tags: Vec<String>,
+        labels: HashMap<String, String>,
+        missing: Option<String>,
+    }
+
+    #[test]
+    fn deserialize_nested_struct() {
+        let input = r#"
+        name = "web"
+        port = 8080
+        enabled = yes
+        mode = "safe"
+        upstream {
+            hosts = "a"
+            timeout = 10s
+        }
+        tags = one
+        tags = two
+        labels { team = infra }
+        "#;
+        let mut parser = Parser::default();
+        parser
+            .add_chunk_full(input, Priority::default(), DEFAULT_DUPLICATE_STRATEGY)
+            .unwrap();
+        let root = parser.get_object().unwrap();
+
+        let config: Config = root.deserialize_into().unwrap();
+        let mut labels = HashMap::new();
+        labels.insert("team".to_string(), "infra".to_string());
+        let expected = Config {
+            name: "web".to_string(),
+            port: 8080,
+            enabled: true,
+            mode: Mode::Safe,
+            upstream: Upstream {
+                hosts: vec!["a".to_string()],
+                timeout: 10.0,
+                weight: None,
+            },
+            tags: vec!["one".to_string(), "two".to_string()],
+            labels,
+            missing: None,
+        };
+        assert_eq!(expected, config);
+        assert_ne!(Mode::Fast, config.mode);
+    }
+
+    #[test]
+    fn deserialize_wrong_type() {
+        let mut parser = Parser::default();
+        parser
+            .add_chunk_full(
+                "port = web",
+                Priority::default(),
+                DEFAULT_DUPLICATE_STRATEGY,
+            )
+            .unwrap();
+        let root = parser.get_object().unwrap();
+
+        let result: Result<HashMap<String, u16>, _> = root.deserialize_into();
+        assert!(result.is_err());
+    }
+}
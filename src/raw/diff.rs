@@ -0,0 +1,177 @@
+//! Structural diff between two objects, e.g. to log what changed on a config reload.
+use crate::raw::object::{Object, ObjectRef};
+use std::fmt;
+
+/// A single difference between two objects. Paths use dot notation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigChange {
+    /// Key is present only in the new object.
+    Added { path: String, value: Object },
+    /// Key is present only in the old object.
+    Removed { path: String, value: Object },
+    /// Key is present in both objects, but values are different.
+    Changed {
+        path: String,
+        old: Object,
+        new: Object,
+    },
+}
+
+impl ConfigChange {
+    /// Path of the changed key.
+    pub fn path(&self) -> &str {
+        match self {
+            ConfigChange::Added { path, .. }
+            | ConfigChange::Removed { path, .. }
+            | ConfigChange::Changed { path, .. } => path,
+        }
+    }
+}
+
+impl fmt::Display for ConfigChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigChange::Added { path, .. } => write!(f, "+ {}", path),
+            ConfigChange::Removed { path, .. } => write!(f, "- {}", path),
+            ConfigChange::Changed { path, old, new } => write!(
+                f,
+                "~ {}: {} -> {}",
+                path,
+                old.emit_json_compact().unwrap_or_default(),
+                new.emit_json_compact().unwrap_or_default()
+            ),
+        }
+    }
+}
+
+/// Render changes one per line, git-style.
+pub fn format_diff(changes: &[ConfigChange]) -> String {
+    changes
+        .iter()
+        .map(ConfigChange::to_string)
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+impl ObjectRef {
+    /// Compare this (old) object with `new` one. Objects are compared key by key recursively, everything else as a whole.
+    ///
+    /// Keys with more than one value (implicit arrays) are compared by their first value only.
+    pub fn diff(&self, new: &ObjectRef) -> Vec<ConfigChange> {
+        let mut changes = Vec::new();
+        diff_into(&mut changes, String::new(), self, new);
+        changes
+    }
+}
+
+fn join_path(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", prefix, key)
+    }
+}
+
+/// Whether two values differ. `PartialEq` truncates difference of numbers to an integer, so floats and times are compared here.
+fn values_differ(old: &ObjectRef, new: &ObjectRef) -> bool {
+    if old.kind() != new.kind() {
+        return true;
+    }
+    if old.is_float() || old.is_time() {
+        return old.as_f64() != new.as_f64();
+    }
+    if old.is_array() {
+        let (old, new) = (old.iterate_raw(true), new.iterate_raw(true));
+        return old.len() != new.len()
+            || old
+                .iter()
+                .zip(new.iter())
+                .any(|(old, new)| values_differ(old, new));
+    }
+    old != new
+}
+
+fn diff_into(changes: &mut Vec<ConfigChange>, path: String, old: &ObjectRef, new: &ObjectRef) {
+    if !(old.is_object() && new.is_object()) {
+        if values_differ(old, new) {
+            changes.push(ConfigChange::Changed {
+                path,
+                old: old.to_owned(),
+                new: new.to_owned(),
+            });
+        }
+        return;
+    }
+    for old_child in old.iterate_raw(true) {
+        let key = old_child.key().unwrap_or_default();
+        let child_path = join_path(&path, &key);
+        match new.lookup(&key) {
+            Some(new_child) => diff_into(changes, child_path, &old_child, &new_child),
+            None => changes.push(ConfigChange::Removed {
+                path: child_path,
+                value: old_child.to_owned(),
+            }),
+        }
+    }
+    for new_child in new.iterate_raw(true) {
+        let key = new_child.key().unwrap_or_default();
+        if old.lookup(&key).is_none() {
+            changes.push(ConfigChange::Added {
+                path: join_path(&path, &key),
+                value: new_child.to_owned(),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Parser, Priority, DEFAULT_DUPLICATE_STRATEGY};
+
+    fn parse(input: &str) -> Object {
+        let mut parser = Parser::default();
+        parser
+            .add_chunk_full(input, Priority::default(), DEFAULT_DUPLICATE_STRATEGY)
+            .unwrap();
+        parser.get_object().unwrap()
+    }
+
+    #[test]
+    fn format_changes() {
+        let old = parse(
+            r#"
+            server { port = 80, name = "web" }
+            legacy { flag = yes }
+            "#,
+        );
+        let new = parse(
+            r#"
+            server { port = 8080, name = "web", tls = yes }
+            legacy {}
+            "#,
+        );
+
+        let changes = old.diff(&new);
+        assert_eq!(
+            vec!["server.port", "server.tls", "legacy.flag"],
+            changes.iter().map(ConfigChange::path).collect::<Vec<_>>()
+        );
+        let expected = "~ server.port: 80 -> 8080\n+ server.tls\n- legacy.flag";
+        assert_eq!(expected, format_diff(&changes));
+
+        assert!(old.diff(&old).is_empty());
+    }
+
+    #[test]
+    fn fractional_changes() {
+        let old = parse("ratio = 0.5\ntimeout = 1.5s\nweights = [0.1, 0.2]\nsame = 0.5");
+        let new = parse("ratio = 0.9\ntimeout = 1.9s\nweights = [0.1, 0.3]\nsame = 0.5");
+
+        let changes = old.diff(&new);
+        assert_eq!(
+            vec!["ratio", "timeout", "weights"],
+            changes.iter().map(ConfigChange::path).collect::<Vec<_>>()
+        );
+    }
+}
@@ -16,33 +16,40 @@
 //!
 //! Single values are automatically converted into arrays as well.
 //!
+//! #### Reverse iteration
+//!
+//! libUCL iterators are forward-only. Both iterators implement `DoubleEndedIterator`: items of explicit arrays are taken by index from the back, for anything else the remaining items are collected on the first call to `next_back`.
+//!
 
 use super::object::ObjectRef;
 use libucl_bind::{
-    ucl_iterate_type, ucl_object_iterate_free, ucl_object_iterate_full, ucl_object_iterate_new,
+    ucl_array_find_index, ucl_iterate_type, ucl_object_iterate_free, ucl_object_iterate_full,
+    ucl_object_iterate_new,
 };
+use std::cell::Cell;
+use std::collections::VecDeque;
 
 pub struct Iter<'data> {
     object: &'data ObjectRef,
-    inner: libucl_bind::ucl_object_iter_t,
+    cursor: Cursor,
 }
 
 impl<'data> Iter<'data> {
     pub fn new(object: &'data ObjectRef) -> Self {
-        let inner = unsafe { ucl_object_iterate_new(object.as_ptr()) };
-        Iter { object, inner }
+        let cursor = Cursor::new(object);
+        Iter { object, cursor }
     }
 }
 
 pub struct IntoIter {
     object: ObjectRef,
-    inner: libucl_bind::ucl_object_iter_t,
+    cursor: Cursor,
 }
 
 impl IntoIter {
     pub fn new(object: ObjectRef) -> Self {
-        let inner = unsafe { ucl_object_iterate_new(object.as_ptr()) };
-        IntoIter { object, inner }
+        let cursor = Cursor::new(&object);
+        IntoIter { object, cursor }
     }
 }
 
@@ -50,7 +57,21 @@ impl<'data> Iterator for Iter<'data> {
     type Item = ObjectRef;
 
     fn next(&mut self) -> Option<Self::Item> {
-        iterate(&self.object, self.inner)
+        self.cursor.next(self.object)
+    }
+
+    /// Exact number of remaining items. Objects are counted on the first call, which takes an extra pass over their keys.
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.cursor.remaining(self.object);
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'data> ExactSizeIterator for Iter<'data> {}
+
+impl<'data> DoubleEndedIterator for Iter<'data> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.cursor.next_back(self.object)
     }
 }
 
@@ -58,23 +79,21 @@ impl Iterator for IntoIter {
     type Item = ObjectRef;
 
     fn next(&mut self) -> Option<Self::Item> {
-        iterate(&self.object, self.inner)
+        self.cursor.next(&self.object)
     }
-}
 
-impl<'data> Drop for Iter<'data> {
-    fn drop(&mut self) {
-        unsafe {
-            ucl_object_iterate_free(self.inner);
-        }
+    /// Exact number of remaining items. Objects are counted on the first call, which takes an extra pass over their keys.
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.cursor.remaining(&self.object);
+        (remaining, Some(remaining))
     }
 }
 
-impl Drop for IntoIter {
-    fn drop(&mut self) {
-        unsafe {
-            ucl_object_iterate_free(self.inner);
-        }
+impl ExactSizeIterator for IntoIter {}
+
+impl DoubleEndedIterator for IntoIter {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.cursor.next_back(&self.object)
     }
 }
 
@@ -96,6 +115,89 @@ impl IntoIterator for ObjectRef {
     }
 }
 
+/// Position of `Iter` and `IntoIter` within the object they iterate over.
+struct Cursor {
+    inner: libucl_bind::ucl_object_iter_t,
+    /// Items taken from the front.
+    front: usize,
+    /// Items taken from the back.
+    back: usize,
+    /// Number of items in the object, counted when it's needed first.
+    len: Cell<Option<usize>>,
+    /// Items left after the first `next_back` on anything but an explicit array.
+    rest: Option<VecDeque<ObjectRef>>,
+}
+
+impl Cursor {
+    fn new(object: &ObjectRef) -> Self {
+        Cursor {
+            inner: unsafe { ucl_object_iterate_new(object.as_ptr()) },
+            front: 0,
+            back: 0,
+            len: Cell::new(None),
+            rest: None,
+        }
+    }
+
+    fn len(&self, object: &ObjectRef) -> usize {
+        match self.len.get() {
+            Some(len) => len,
+            None => {
+                let len = object.iter_len();
+                self.len.set(Some(len));
+                len
+            }
+        }
+    }
+
+    fn remaining(&self, object: &ObjectRef) -> usize {
+        match self.rest {
+            Some(ref rest) => rest.len(),
+            None => self.len(object).saturating_sub(self.front + self.back),
+        }
+    }
+
+    fn next(&mut self, object: &ObjectRef) -> Option<ObjectRef> {
+        let next = match self.rest {
+            Some(ref mut rest) => rest.pop_front(),
+            // Only explicit arrays are taken from the back without collecting the rest.
+            None if self.back > 0 && self.front + self.back >= self.len(object) => None,
+            None => iterate(object, self.inner),
+        };
+        if next.is_some() {
+            self.front += 1;
+        }
+        next
+    }
+
+    fn next_back(&mut self, object: &ObjectRef) -> Option<ObjectRef> {
+        let next = if self.rest.is_none() && object.is_standalone_array() {
+            let len = self.len(object);
+            if self.front + self.back >= len {
+                return None;
+            }
+            iterate_back(object, len - self.back)
+        } else {
+            let inner = self.inner;
+            self.rest
+                .get_or_insert_with(|| collect_rest(object, inner))
+                .pop_back()
+        };
+        if next.is_some() {
+            self.back += 1;
+        }
+        next
+    }
+}
+
+impl Drop for Cursor {
+    fn drop(&mut self) {
+        unsafe {
+            ucl_object_iterate_free(self.inner);
+        }
+    }
+}
+
 fn iterate(_object: &ObjectRef, iterator: libucl_bind::ucl_object_iter_t) -> Option<ObjectRef> {
     // Bail early if iterator didn't initialize.
     if iterator.is_null() {
@@ -106,6 +208,21 @@ fn iterate(_object: &ObjectRef, iterator: libucl_bind::ucl_object_iter_t) -> Opt
     ObjectRef::from_c_ptr(obj_ptr)
 }
 
+/// Every item the iterator hasn't yielded yet.
+fn collect_rest(
+    object: &ObjectRef,
+    iterator: libucl_bind::ucl_object_iter_t,
+) -> VecDeque<ObjectRef> {
+    std::iter::from_fn(|| iterate(object, iterator)).collect()
+}
+
+/// Item right before `end`. Object must be an explicit array.
+fn iterate_back(object: &ObjectRef, end: usize) -> Option<ObjectRef> {
+    let obj_ptr = unsafe { ucl_array_find_index(object.as_ptr(), (end - 1) as u32) };
+
+    ObjectRef::from_c_ptr(obj_ptr)
+}
+
 #[cfg(test)]
 mod test {
     use crate::*;
@@ -238,4 +355,100 @@ mod test {
 
         assert!(iter.next().is_none());
     }
+
+    #[test]
+    fn exact_size() {
+        let mut parser = Parser::default();
+        let input = r#"
+            array = [1, 2, 3]
+            empty = []
+            dict { a = 1, b = 2, b = 3, c { d = 4 } }
+            key = "value1"
+            key = "value2"
+            key = "value3"
+            arrays = [1, 2]
+            arrays = [3]
+            single = 1
+        "#;
+
+        parser
+            .add_chunk_full(input, Priority::default(), DEFAULT_DUPLICATE_STRATEGY)
+            .unwrap();
+        let result = parser.get_object().unwrap();
+
+        assert_eq!(6, result.iter().len());
+        for (key, expected) in [
+            ("array", 3),
+            ("empty", 0),
+            ("dict", 3),
+            ("key", 3),
+            ("arrays", 3),
+            ("single", 1),
+        ] {
+            let obj = result.lookup(key).unwrap();
+            assert_eq!(expected, obj.iter().count(), "{}", key);
+            assert_eq!(expected, obj.iter().len(), "{}", key);
+            assert_eq!(expected, obj.entry().1.into_iter().len(), "{}", key);
+        }
+
+        let mut iter = result.lookup("array").unwrap().into_iter();
+        iter.next();
+        assert_eq!(2, iter.len());
+        iter.next();
+        iter.next();
+        assert_eq!(0, iter.len());
+        assert!(iter.next().is_none());
+        assert_eq!(0, iter.len());
+    }
+
+    #[test]
+    fn double_ended() {
+        let mut parser = Parser::default();
+        let input = r#"
+            array = [1, 2, 3, 4]
+            key = 1
+            key = 2
+        "#;
+
+        parser
+            .add_chunk_full(input, Priority::default(), DEFAULT_DUPLICATE_STRATEGY)
+            .unwrap();
+        let result = parser.get_object().unwrap();
+        let array = result.lookup("array").unwrap();
+
+        let forward: Vec<i64> = array.iter().map(|obj| obj.as_i64().unwrap()).collect();
+        let backward: Vec<i64> = array
+            .iter()
+            .rev()
+            .map(|obj| obj.as_i64().unwrap())
+            .collect();
+        assert_eq!(forward.into_iter().rev().collect::<Vec<_>>(), backward);
+
+        let mut iter = array.entry().1.into_iter();
+        assert_eq!(Some(1), iter.next().and_then(|obj| obj.as_i64()));
+        assert_eq!(Some(4), iter.next_back().and_then(|obj| obj.as_i64()));
+        assert_eq!(2, iter.len());
+        assert_eq!(Some(3), iter.next_back().and_then(|obj| obj.as_i64()));
+        assert_eq!(Some(2), iter.next().and_then(|obj| obj.as_i64()));
+        assert!(iter.next().is_none());
+        assert!(iter.next_back().is_none());
+
+        let implicit = result.lookup("key").unwrap();
+        let backward: Vec<i64> = implicit
+            .iter()
+            .rev()
+            .map(|obj| obj.as_i64().unwrap())
+            .collect();
+        assert_eq!(vec![2, 1], backward);
+
+        let keys: Vec<String> = result.iter().rev().filter_map(|obj| obj.key()).collect();
+        assert_eq!(vec!["key", "array"], keys);
+
+        let mut iter = implicit.iter();
+        assert_eq!(Some(2), iter.next_back().and_then(|obj| obj.as_i64()));
+        assert_eq!(1, iter.len());
+        assert_eq!(Some(1), iter.next().and_then(|obj| obj.as_i64()));
+        assert!(iter.next().is_none());
+        assert!(iter.next_back().is_none());
+    }
 }
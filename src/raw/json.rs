@@ -0,0 +1,131 @@
+//! Conversion of objects into `serde_json::Value`.
+//!
+//! Time objects become plain numbers of seconds. Keys with more than one value (implicit arrays) become arrays.
+use crate::raw::object::{ObjectError, ObjectRef};
+use crate::traits::FromObject;
+use libucl_bind::ucl_type_t;
+use serde_json::{Map, Number, Value};
+
+impl ObjectRef {
+    /// Convert this object into `serde_json::Value`.
+    pub fn to_json_value(&self) -> Value {
+        match self.kind() {
+            ucl_type_t::UCL_OBJECT => Value::Object(self.to_json_map().unwrap_or_default()),
+            ucl_type_t::UCL_ARRAY => Value::Array(
+                self.iterate_raw(true)
+                    .iter()
+                    .map(ObjectRef::to_json_value)
+                    .collect(),
+            ),
+            ucl_type_t::UCL_INT => self.as_i64().map(Value::from).unwrap_or(Value::Null),
+            ucl_type_t::UCL_FLOAT | ucl_type_t::UCL_TIME => self
+                .as_f64()
+                .and_then(Number::from_f64)
+                .map(Value::Number)
+                .unwrap_or(Value::Null),
+            ucl_type_t::UCL_STRING => self.as_string().map(Value::String).unwrap_or(Value::Null),
+            ucl_type_t::UCL_BOOLEAN => self.as_bool().map(Value::Bool).unwrap_or(Value::Null),
+            ucl_type_t::UCL_USERDATA | ucl_type_t::UCL_NULL => Value::Null,
+        }
+    }
+
+    /// Convert this object into JSON map. Returns None if this is not an object.
+    pub fn to_json_map(&self) -> Option<Map<String, Value>> {
+        if !self.is_object() {
+            return None;
+        }
+        let map = self
+            .iterate_raw(true)
+            .into_iter()
+            .map(|child| {
+                let key = child.key().unwrap_or_default();
+                let mut values = child.iterate_raw(false);
+                let value = if values.len() == 1 {
+                    values.remove(0).to_json_value()
+                } else {
+                    Value::Array(values.iter().map(ObjectRef::to_json_value).collect())
+                };
+                (key, value)
+            })
+            .collect();
+        Some(map)
+    }
+}
+
+impl FromObject<ObjectRef> for Value {
+    fn try_from(value: ObjectRef) -> Result<Self, ObjectError> {
+        Ok(value.to_json_value())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Parser, Priority, DEFAULT_DUPLICATE_STRATEGY};
+    use serde_json::json;
+
+    #[test]
+    fn nested_object_to_json_map() {
+        let input = r#"
+        name = "web"
+        port = 8080
+        ratio = 0.5
+        enabled = yes
+        upstream {
+            hosts = ["a", "b"]
+            timeout = 10s
+        }
+        tag = one
+        tag = two
+        "#;
+        let mut parser = Parser::default();
+        parser
+            .add_chunk_full(input, Priority::default(), DEFAULT_DUPLICATE_STRATEGY)
+            .unwrap();
+        let root = parser.get_object().unwrap();
+
+        let map = root.to_json_map().unwrap();
+        let expected = json!({
+            "name": "web",
+            "port": 8080,
+            "ratio": 0.5,
+            "enabled": true,
+            "upstream": {
+                "hosts": ["a", "b"],
+                "timeout": 10.0
+            },
+            "tag": ["one", "two"]
+        });
+        assert_eq!(expected, serde_json::Value::Object(map));
+
+        assert!(root.lookup("name").unwrap().to_json_map().is_none());
+    }
+
+    #[test]
+    fn emit_json_round_trip() {
+        let input = r#"
+        name = "web"
+        upstream {
+            hosts = ["a", "b"]
+            port = 8080
+            enabled = yes
+        }
+        "#;
+        let mut parser = Parser::default();
+        parser
+            .add_chunk_full(input, Priority::default(), DEFAULT_DUPLICATE_STRATEGY)
+            .unwrap();
+        let root = parser.get_object().unwrap();
+
+        let pretty: serde_json::Value = serde_json::from_str(&root.emit_json().unwrap()).unwrap();
+        let compact: serde_json::Value =
+            serde_json::from_str(&root.emit_json_compact().unwrap()).unwrap();
+        assert_eq!(root.to_json_value(), pretty);
+        assert_eq!(pretty, compact);
+
+        let upstream = root.lookup("upstream").unwrap();
+        let emitted: serde_json::Value =
+            serde_json::from_str(&upstream.emit_json_compact().unwrap()).unwrap();
+        let expected = json!({"hosts": ["a", "b"], "port": 8080, "enabled": true});
+        assert_eq!(expected, emitted);
+    }
+}
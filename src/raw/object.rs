@@ -21,35 +21,60 @@
 //! 3. Content of objects
 //!
 //! That means you can compare a string to float, and it will give some result. I'm not sure about usefulness of this, but it is totally possible.
+//!
+//! ### Hashing
+//!
+//! `Hash` is consistent with equality. libUCL compares numbers by truncating their difference to an integer, so floats and times within `1.0` of each other are equal. Because of that floats and times are hashed by type only, and objects are hashed by type and size only.
+use crate::error::{UclSchemaError, UclSchemaErrorType};
 use crate::raw::iterator::Iter;
-use crate::raw::{utils, Priority};
+use crate::raw::{utils, DuplicateStrategy, Priority};
 use crate::traits::FromObject;
 use bitflags::_core::borrow::Borrow;
 use bitflags::_core::cmp::Ordering;
 use bitflags::_core::convert::Infallible;
 use bitflags::_core::fmt::{Display, Formatter};
 use libucl_bind::{
-    ucl_object_compare, ucl_object_copy, ucl_object_frombool, ucl_object_fromdouble,
-    ucl_object_fromint, ucl_object_fromstring, ucl_object_get_priority, ucl_object_key,
-    ucl_object_lookup, ucl_object_lookup_path, ucl_object_ref, ucl_object_t,
-    ucl_object_toboolean_safe, ucl_object_todouble_safe, ucl_object_toint_safe,
-    ucl_object_tostring_forced, ucl_object_tostring_safe, ucl_object_type, ucl_object_unref,
-    ucl_type_t,
+    ucl_array_append, ucl_array_pop_last, ucl_array_size, ucl_emitter, ucl_iterate_type,
+    ucl_object_compare, ucl_object_copy, ucl_object_emit, ucl_object_frombool,
+    ucl_object_fromdouble, ucl_object_fromint, ucl_object_fromstring, ucl_object_fromstring_common,
+    ucl_object_get_priority, ucl_object_insert_key, ucl_object_iter_t, ucl_object_iterate_free,
+    ucl_object_iterate_full, ucl_object_iterate_new, ucl_object_key, ucl_object_lookup,
+    ucl_object_lookup_path_char, ucl_object_new_full, ucl_object_ref, ucl_object_replace_key,
+    ucl_object_set_priority, ucl_object_t, ucl_object_toboolean_safe, ucl_object_todouble_safe,
+    ucl_object_toint_safe, ucl_object_tostring_forced, ucl_object_tostring_safe, ucl_object_type,
+    ucl_object_unref, ucl_object_validate, ucl_schema_error, ucl_string_flags, ucl_type_t,
 };
-use std::borrow::ToOwned;
-use std::collections::HashMap;
+use std::any::Any;
+use std::borrow::{Cow, ToOwned};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::convert::TryInto;
 use std::error::Error;
-use std::ffi::CStr;
+use std::ffi::{CStr, OsString};
 use std::fmt;
-use std::hash::BuildHasher;
+use std::hash::{BuildHasher, Hash, Hasher};
 use std::mem::MaybeUninit;
 use std::net::{AddrParseError, SocketAddr};
-use std::num::TryFromIntError;
+use std::num::{
+    NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU16, NonZeroU32,
+    NonZeroU64, NonZeroU8, NonZeroUsize, TryFromIntError,
+};
 use std::ops::{Deref, DerefMut};
+use std::os::raw::c_char;
 use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::Arc;
 use std::time::Duration;
 
+extern "C" {
+    // `ucl_object_iterate` is a macro, so bindings don't have it.
+    fn ucl_object_iterate_with_error(
+        obj: *const ucl_object_t,
+        iter: *mut ucl_object_iter_t,
+        expand_values: bool,
+        ep: *mut std::os::raw::c_int,
+    ) -> *const ucl_object_t;
+}
+
 /// Errors that could be returned by `Object` or `ObjectRef` functions.
 #[derive(Eq, PartialEq, Debug, Clone)]
 pub enum ObjectError {
@@ -68,11 +93,46 @@ pub enum ObjectError {
     AddrParseError(AddrParseError),
     /// An error that we couldn't match to internal type.
     Other(String),
+    /// An error that we couldn't match to internal type, but with the original error preserved.
+    OtherWithSource {
+        message: String,
+        source: ErrorSource,
+    },
     /// Not an error, but required for some conversions.
     None,
 }
 
-impl Error for ObjectError {}
+/// Original error wrapped by `ObjectError::OtherWithSource`.
+///
+/// Source is reference counted, so `ObjectError` can stay `Clone`. Two sources are equal only if they point to the same error.
+#[derive(Debug, Clone)]
+pub struct ErrorSource(Arc<dyn Error + Send + Sync>);
+
+impl ErrorSource {
+    /// Get a reference to the original error.
+    pub fn get_ref(&self) -> &(dyn Error + Send + Sync + 'static) {
+        &*self.0
+    }
+}
+
+impl PartialEq for ErrorSource {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for ErrorSource {}
+
+impl Error for ObjectError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ObjectError::IntConversionError(e) => Some(e),
+            ObjectError::AddrParseError(e) => Some(e),
+            ObjectError::OtherWithSource { source, .. } => Some(source.get_ref()),
+            _ => None,
+        }
+    }
+}
 
 impl ObjectError {
     /// Wrap error in Box<>.
@@ -81,7 +141,7 @@ impl ObjectError {
     }
 
     /// Wrap error in Box<> and erase its type.
-    pub fn boxed_dyn(self) -> Box<dyn Error> {
+    pub fn boxed_dyn(self) -> Box<dyn Error + Send + Sync> {
         Box::new(self)
     }
 
@@ -89,6 +149,27 @@ impl ObjectError {
     pub fn other<E: Display>(err: E) -> ObjectError {
         ObjectError::Other(err.to_string())
     }
+
+    /// Convert an error returned by a field validator. `ObjectError` is returned as is, anything else becomes `Other`.
+    pub fn from_validation<E: Display + 'static>(err: E) -> ObjectError {
+        let mut err = Some(err);
+        if let Some(err) = (&mut err as &mut dyn Any).downcast_mut::<Option<ObjectError>>() {
+            return err.take().expect("Error is taken once");
+        }
+        ObjectError::other(err.expect("Error is taken once"))
+    }
+
+    /// Create a new error `OtherWithSource` that keeps the original error available via `Error::source`.
+    pub fn other_with_source<M, E>(message: M, err: E) -> ObjectError
+    where
+        M: Display,
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        ObjectError::OtherWithSource {
+            message: message.to_string(),
+            source: ErrorSource(Arc::from(err.into())),
+        }
+    }
 }
 impl From<Infallible> for ObjectError {
     fn from(_: Infallible) -> Self {
@@ -107,6 +188,13 @@ impl From<TryFromIntError> for ObjectError {
     }
 }
 
+impl From<ObjectError> for std::io::Error {
+    /// Always `ErrorKind::InvalidData`, original error is kept as the inner error.
+    fn from(err: ObjectError) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, err)
+    }
+}
+
 impl fmt::Display for ObjectError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -117,17 +205,64 @@ impl fmt::Display for ObjectError {
                 wanted_type,
             } => write!(
                 f,
-                "Key \"{}\" actual type is {:?} and not {:?}",
-                key, actual_type, wanted_type
+                "Key \"{}\" actual type is {} and not {}",
+                key,
+                type_name(*actual_type),
+                type_name(*wanted_type)
             ),
             ObjectError::IntConversionError(e) => e.fmt(f),
             ObjectError::AddrParseError(e) => e.fmt(f),
             ObjectError::Other(e) => e.fmt(f),
+            ObjectError::OtherWithSource { message, .. } => message.fmt(f),
             ObjectError::None => write!(f, "Impossible error was possible after all."),
         }
     }
 }
 
+/// Human readable name of the type.
+fn type_name(kind: ucl_type_t) -> &'static str {
+    match kind {
+        ucl_type_t::UCL_OBJECT => "object",
+        ucl_type_t::UCL_ARRAY => "array",
+        ucl_type_t::UCL_INT => "int",
+        ucl_type_t::UCL_FLOAT => "float",
+        ucl_type_t::UCL_STRING => "string",
+        ucl_type_t::UCL_BOOLEAN => "boolean",
+        ucl_type_t::UCL_TIME => "time",
+        ucl_type_t::UCL_USERDATA => "userdata",
+        ucl_type_t::UCL_NULL => "null",
+    }
+}
+
+/// Size in bytes.
+///
+/// libUCL turns size literals into plain integers while parsing: `1kb` is 1024, `1k` is 1000. Any non-negative integer can be read as a size.
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Copy, Clone, Hash, Default)]
+pub struct ByteSize(pub u64);
+
+impl ByteSize {
+    /// Size in bytes.
+    pub fn as_bytes(self) -> u64 {
+        self.0
+    }
+
+    /// Size in whole kibibytes, rounded down.
+    pub fn as_kib(self) -> u64 {
+        self.0 / 1024
+    }
+
+    /// Size in whole mebibytes, rounded down.
+    pub fn as_mib(self) -> u64 {
+        self.0 / (1024 * 1024)
+    }
+}
+
+impl From<ByteSize> for u64 {
+    fn from(source: ByteSize) -> Self {
+        source.0
+    }
+}
+
 /// Owned and mutable instance of UCL Object.
 /// All methods that do not require mutability should be implemented on `ObjectRef` instead.
 #[derive(Eq)]
@@ -170,6 +305,172 @@ impl Object {
     pub(crate) fn from_c_ptr(object: *const ucl_object_t) -> Option<Object> {
         ObjectRef::from_c_ptr(object).map(|obj_ref| Object { inner: obj_ref })
     }
+
+    /// Change priority of this object.
+    ///
+    /// Clones of `Object` share the same underlying object, so they will see the new priority as well. Use `with_priority` to get a separate copy.
+    pub fn set_priority(&mut self, priority: Priority) {
+        unsafe { ucl_object_set_priority(self.as_mut_ptr(), priority.as_c_uint()) }
+    }
+
+    /// Create an empty object.
+    pub fn new_object() -> Object {
+        let ptr = unsafe { ucl_object_new_full(ucl_type_t::UCL_OBJECT, 0) };
+        Object::from_c_ptr(ptr).expect("Failed to construct an object.")
+    }
+
+    /// Create an empty array.
+    pub fn new_array() -> Object {
+        let ptr = unsafe { ucl_object_new_full(ucl_type_t::UCL_ARRAY, 0) };
+        Object::from_c_ptr(ptr).expect("Failed to construct an object.")
+    }
+
+    /// Insert `value` under `key`. If the key is already present, values form an implicit array.
+    ///
+    /// Returns `false` and drops `value` if this is not an object. Clones of `Object` share the same underlying object, so they will see the new key as well.
+    pub fn insert<K: AsRef<str>>(&mut self, key: K, value: Object) -> bool {
+        if !self.is_object() {
+            return false;
+        }
+        let key = key.as_ref();
+        let inserted = unsafe {
+            ucl_object_insert_key(
+                self.as_mut_ptr(),
+                value.object,
+                key.as_ptr() as *const _,
+                key.len(),
+                true,
+            )
+        };
+        if inserted {
+            // Ownership is transferred to the container.
+            std::mem::forget(value);
+        }
+        inserted
+    }
+
+    /// Insert `value` under `key`, replacing a value that is already there.
+    ///
+    /// Returns `false` and drops `value` if this is not an object. Clones of `Object` share the same underlying object, so they will see the new value as well.
+    pub fn replace<K: AsRef<str>>(&mut self, key: K, value: Object) -> bool {
+        if !self.is_object() {
+            return false;
+        }
+        let key = key.as_ref();
+        // libUCL returns `false` when there was nothing to replace, but the value is inserted either way.
+        unsafe {
+            ucl_object_replace_key(
+                self.as_mut_ptr(),
+                value.object,
+                key.as_ptr() as *const _,
+                key.len(),
+                true,
+            )
+        };
+        // Ownership is transferred to the container.
+        std::mem::forget(value);
+        true
+    }
+
+    /// Append `value` to the end of this array.
+    ///
+    /// Returns `false` and drops `value` if this is not an array. Clones of `Object` share the same underlying object, so they will see the new element as well.
+    pub fn array_push(&mut self, value: Object) -> bool {
+        // libUCL turns anything into an array here, so the type is checked upfront.
+        if !self.is_array() {
+            return false;
+        }
+        let pushed = unsafe { ucl_array_append(self.as_mut_ptr(), value.object) };
+        if pushed {
+            // Ownership is transferred to the container.
+            std::mem::forget(value);
+        }
+        pushed
+    }
+
+    /// Merge keys of `other` into this object. Keys present in both are resolved by `strategy` the same way the parser resolves keys repeated across chunks:
+    ///
+    /// - `UCL_DUPLICATE_APPEND`: equal priorities form an implicit array, otherwise the value with higher priority wins.
+    /// - `UCL_DUPLICATE_MERGE`: objects are merged recursively and arrays are extended, other values are treated as with `UCL_DUPLICATE_APPEND`.
+    /// - `UCL_DUPLICATE_REWRITE`: value from `other` wins.
+    /// - `UCL_DUPLICATE_ERROR`: error is returned. Keys merged before the duplicate stay merged.
+    ///
+    /// Values of `other` are copied. Clones of `Object` share the same underlying object, so they will see the merged keys as well.
+    pub fn merge(&mut self, other: Object, strategy: DuplicateStrategy) -> Result<(), ObjectError> {
+        for obj in [&**self, &*other].iter() {
+            if !obj.is_object() {
+                return Err(ObjectError::WrongType {
+                    key: obj.key().unwrap_or_default(),
+                    actual_type: obj.kind,
+                    wanted_type: ucl_type_t::UCL_OBJECT,
+                });
+            }
+        }
+        merge_keys(self, &other, strategy)
+    }
+}
+
+/// Merge keys of `other` into `top`, both must be objects.
+fn merge_keys(
+    top: &ObjectRef,
+    other: &ObjectRef,
+    strategy: DuplicateStrategy,
+) -> Result<(), ObjectError> {
+    let top_ptr = top.object as *mut ucl_object_t;
+    for child in other.iterate_raw(true) {
+        let key = child.key().unwrap_or_default();
+        let found = match top.lookup(&key) {
+            Some(found) => found,
+            None => {
+                unsafe {
+                    ucl_object_insert_key(
+                        top_ptr,
+                        ucl_object_copy(child.object),
+                        key.as_ptr() as *const _,
+                        key.len(),
+                        true,
+                    )
+                };
+                continue;
+            }
+        };
+        match strategy {
+            DuplicateStrategy::UCL_DUPLICATE_ERROR => {
+                return Err(ObjectError::Other(format!(
+                    "duplicate element for key '{}' found",
+                    key
+                )));
+            }
+            DuplicateStrategy::UCL_DUPLICATE_MERGE if found.is_object() && child.is_object() => {
+                merge_keys(&found, &child, strategy)?;
+                continue;
+            }
+            DuplicateStrategy::UCL_DUPLICATE_MERGE if found.is_array() => {
+                for elt in child.elements() {
+                    let copy = elt.copy_single();
+                    unsafe { ucl_array_append(found.object as *mut ucl_object_t, copy.object) };
+                    // Ownership is transferred to the container.
+                    std::mem::forget(copy);
+                }
+                continue;
+            }
+            _ => {}
+        }
+        let replace = strategy == DuplicateStrategy::UCL_DUPLICATE_REWRITE
+            || found.priority() < child.priority();
+        if !replace && found.priority() > child.priority() {
+            continue;
+        }
+        unsafe {
+            let copy = ucl_object_copy(child.object);
+            if replace {
+                ucl_object_replace_key(top_ptr, copy, key.as_ptr() as *const _, key.len(), true);
+            } else {
+                ucl_object_insert_key(top_ptr, copy, key.as_ptr() as *const _, key.len(), true);
+            }
+        }
+    }
+    Ok(())
 }
 
 /// Objects may not actually dropped, but their reference count is decreased.
@@ -225,6 +526,13 @@ impl ObjectRef {
         Object::from_c_ptr(ptr).expect("Got Object with null ptr")
     }
 
+    /// Perform a deep copy and set priority of the copy.
+    pub fn with_priority(&self, priority: Priority) -> Object {
+        let mut copy = self.deep_copy();
+        copy.set_priority(priority);
+        copy
+    }
+
     /// Returns `true` if this object is a null.
     pub fn is_null(&self) -> bool {
         self.kind == ucl_type_t::UCL_NULL
@@ -265,23 +573,74 @@ impl ObjectRef {
         self.kind == ucl_type_t::UCL_TIME
     }
 
+    /// Returns `true` if this object is a leaf value: integer, float, string, boolean, time or null.
+    pub fn is_scalar(&self) -> bool {
+        !matches!(
+            self.kind,
+            ucl_type_t::UCL_OBJECT | ucl_type_t::UCL_ARRAY | ucl_type_t::UCL_USERDATA
+        )
+    }
+
     /// Get priority assigned to the object.
     pub fn priority(&self) -> Priority {
         let out = unsafe { ucl_object_get_priority(self.object) };
         Priority::from(out)
     }
 
+    /// Whether this object is an explicit array that isn't a part of an implicit array.
+    pub(crate) fn is_standalone_array(&self) -> bool {
+        self.is_array() && self.iterate_raw(false).len() == 1
+    }
+
+    /// Number of items that iterators over this object yield.
+    ///
+    /// libUCL's `len` of an object also counts values of implicit arrays, so keys of objects are counted by iterating over them.
+    pub(crate) fn iter_len(&self) -> usize {
+        self.iterate_raw(false)
+            .iter()
+            .map(|elt| match elt.kind {
+                ucl_type_t::UCL_ARRAY => unsafe { ucl_array_size(elt.object) as usize },
+                ucl_type_t::UCL_OBJECT => unsafe {
+                    let iter = ucl_object_iterate_new(elt.object);
+                    let mut keys = 0;
+                    while !ucl_object_iterate_full(iter, ucl_iterate_type::UCL_ITERATE_EXPLICIT)
+                        .is_null()
+                    {
+                        keys += 1;
+                    }
+                    ucl_object_iterate_free(iter);
+                    keys
+                },
+                _ => 1,
+            })
+            .sum()
+    }
+
     /// Get type/kind of given object
     pub fn kind(&self) -> ucl_type_t {
         self.kind
     }
 
+    /// Get human readable name of the object's type, e.g. `"object"` or `"int"`.
+    pub fn type_name(&self) -> &'static str {
+        type_name(self.kind)
+    }
+
     /// Get key assigned to the object
     pub fn key(&self) -> Option<String> {
         let c_str = unsafe { ucl_object_key(self.object) };
         utils::to_str(c_str)
     }
 
+    /// Get key assigned to the object together with the object itself. Handy in iterator adaptors.
+    pub fn entry(&self) -> (Option<String>, ObjectRef) {
+        let obj = ObjectRef {
+            object: self.object,
+            kind: self.kind,
+        };
+        (self.key(), obj)
+    }
+
     /// Lookup a key within an object with type Object.
     pub fn lookup<K: AsRef<str>>(&self, key: K) -> Option<ObjectRef> {
         if !self.is_object() {
@@ -292,13 +651,69 @@ impl ObjectRef {
         ObjectRef::from_c_ptr(obj as *mut ucl_object_t)
     }
 
+    /// Alias for `lookup`.
+    ///
+    /// There is no `Index` implementation, because `lookup` creates a new `ObjectRef` and `Index` can only return a reference.
+    pub fn get<K: AsRef<str>>(&self, key: K) -> Option<ObjectRef> {
+        self.lookup(key)
+    }
+
+    /// Same as `lookup`, but tells why the lookup failed: `WrongType` if this is not an object, `KeyNotFound` if key is missing.
+    pub fn try_lookup<K: AsRef<str>>(&self, key: K) -> Result<ObjectRef, ObjectError> {
+        if !self.is_object() {
+            return Err(ObjectError::WrongType {
+                key: self.key().unwrap_or_default(),
+                actual_type: self.kind,
+                wanted_type: ucl_type_t::UCL_OBJECT,
+            });
+        }
+        let key = key.as_ref();
+        self.lookup(key)
+            .ok_or_else(|| ObjectError::KeyNotFound(key.to_string()))
+    }
+
     /// Perform a nested lookup with dot notation.
     pub fn lookup_path<K: AsRef<str>>(&self, path: K) -> Option<ObjectRef> {
-        if !self.is_object() {
+        self.lookup_path_sep(path, '.')
+    }
+
+    /// Perform a nested lookup with a custom separator, e.g. to look up keys that have dots in them: `lookup_path_sep("domains/example.com", '/')`.
+    ///
+    /// Separator must be an ASCII character, otherwise `None` is returned.
+    pub fn lookup_path_sep<K: AsRef<str>>(&self, path: K, sep: char) -> Option<ObjectRef> {
+        if !self.is_object() || !sep.is_ascii() {
             return None;
         }
         let key = utils::to_c_string(path);
-        let obj = unsafe { ucl_object_lookup_path(self.object, key.as_ptr()) };
+        let obj = unsafe { ucl_object_lookup_path_char(self.object, key.as_ptr(), sep as c_char) };
+        ObjectRef::from_c_ptr(obj as *mut ucl_object_t)
+    }
+
+    /// Same as `lookup`, but returns an owned object that outlives its parent.
+    pub fn lookup_owned<K: AsRef<str>>(&self, key: K) -> Option<Object> {
+        self.lookup(key).as_ref().map(ToOwned::to_owned)
+    }
+
+    /// Same as `lookup_path`, but returns an owned object that outlives its parent.
+    pub fn lookup_path_owned<K: AsRef<str>>(&self, path: K) -> Option<Object> {
+        self.lookup_path(path).as_ref().map(ToOwned::to_owned)
+    }
+
+    /// Perform a nested lookup with dot notation without allocating a C string for the path.
+    ///
+    /// `path` must be NUL-terminated (e.g. `b"section.key\0"`) and must not contain other NUL bytes, otherwise `None` is returned.
+    /// Useful in hot code with static paths, derived `FromObject` implementations use it for every field.
+    pub fn lookup_path_bytes(&self, path: &[u8]) -> Option<ObjectRef> {
+        self.lookup_path_bytes_sep(path, b'.')
+    }
+
+    /// Same as `lookup_path_bytes`, but with a custom separator. Separator must be an ASCII character, otherwise `None` is returned.
+    pub fn lookup_path_bytes_sep(&self, path: &[u8], sep: u8) -> Option<ObjectRef> {
+        if !self.is_object() || !sep.is_ascii() {
+            return None;
+        }
+        let key = CStr::from_bytes_with_nul(path).ok()?;
+        let obj = unsafe { ucl_object_lookup_path_char(self.object, key.as_ptr(), sep as c_char) };
         ObjectRef::from_c_ptr(obj as *mut ucl_object_t)
     }
     /// Return string value or None.
@@ -316,6 +731,14 @@ impl ObjectRef {
         }
     }
 
+    /// Return string value quoted for a POSIX shell or None.
+    ///
+    /// Value is wrapped in single quotes, embedded single quotes are written as `'\''`.
+    pub fn as_shell_quoted(&self) -> Option<String> {
+        self.as_string()
+            .map(|value| format!("'{}'", value.replace('\'', "'\\''")))
+    }
+
     /// Return an integer value or None.
     pub fn as_i64(&self) -> Option<i64> {
         if !self.is_integer() {
@@ -331,6 +754,18 @@ impl ObjectRef {
         }
     }
 
+    /// Return an unsigned integer value or None.
+    ///
+    /// libUCL stores all integers as `i64`, so values above `i64::MAX` can't be represented in the first place. Negative values are not reinterpreted and return None.
+    pub fn as_u64(&self) -> Option<u64> {
+        self.as_i64().and_then(|val| val.try_into().ok())
+    }
+
+    /// Return an integer value as a size in bytes or None. Negative integers are rejected.
+    pub fn as_byte_size(&self) -> Option<ByteSize> {
+        self.as_u64().map(ByteSize)
+    }
+
     /// Return a float number of seconds. Only works if object is time.
     pub fn as_time(&self) -> Option<f64> {
         if !self.is_time() {
@@ -340,6 +775,8 @@ impl ObjectRef {
     }
 
     /// Return a float value or None. This function also works on time object.
+    ///
+    /// Integers are rejected, use `as_f64_or_int` to accept them as well. `FromObject` for `f64` accepts both.
     pub fn as_f64(&self) -> Option<f64> {
         if !(self.is_float() || self.is_time()) {
             return None;
@@ -354,6 +791,14 @@ impl ObjectRef {
         }
     }
 
+    /// Return a float value or None. Unlike `as_f64` this also accepts integers.
+    ///
+    /// Integers are cast, so values above 2^53 may lose precision.
+    pub fn as_f64_or_int(&self) -> Option<f64> {
+        self.as_f64()
+            .or_else(|| self.as_i64().map(|val| val as f64))
+    }
+
     /// Return a boolean value or None.
     pub fn as_bool(&self) -> Option<bool> {
         if !self.is_boolean() {
@@ -377,10 +822,190 @@ impl ObjectRef {
         Some(())
     }
 
+    /// Return all entries of an object keyed by their keys or None. Values are not converted, keys with more than one value are returned as implicit arrays.
+    pub fn as_object(&self) -> Option<HashMap<String, ObjectRef>> {
+        if !self.is_object() {
+            return None;
+        }
+        Some(
+            self.iter()
+                .filter_map(|obj| obj.key().map(|key| (key, obj)))
+                .collect(),
+        )
+    }
+
     /// Preferred way to construct an iterator. Items returned by this iterator are always `ObjectRef`.
-    pub fn iter(&self) -> Iter {
+    pub fn iter(&self) -> Iter<'_> {
         Iter::new(self)
     }
+
+    /// Convert every element into `T`, same as `Vec<T>` conversion, but report index of the element that failed alongside the error.
+    pub fn to_vec_indexed<T: FromObject<ObjectRef>>(&self) -> Result<Vec<T>, (usize, ObjectError)> {
+        self.elements()
+            .into_iter()
+            .enumerate()
+            .map(|(idx, obj)| T::try_from(obj).map_err(|err| (idx, err)))
+            .collect()
+    }
+
+    /// Convert every element into `T`, skipping elements that can't be converted.
+    pub fn iter_as<T: FromObject<ObjectRef>>(&self) -> impl Iterator<Item = T> {
+        self.elements()
+            .into_iter()
+            .filter_map(|obj| T::try_from(obj).ok())
+    }
+
+    /// Convert every element into `T`, stopping at the first element that can't be converted.
+    pub fn try_collect_as<T: FromObject<ObjectRef>>(&self) -> Result<Vec<T>, ObjectError> {
+        self.elements().into_iter().map(T::try_from).collect()
+    }
+
+    /// Validate this object against a schema. libUCL supports a subset of JSON Schema draft 4, and schema itself can be written in any format the parser accepts.
+    pub fn validate(&self, schema: &ObjectRef) -> Result<(), UclSchemaError> {
+        let mut err = MaybeUninit::<ucl_schema_error>::zeroed();
+        let valid = unsafe { ucl_object_validate(schema.as_ptr(), self.object, err.as_mut_ptr()) };
+        if valid {
+            return Ok(());
+        }
+        let err = unsafe { err.assume_init() };
+        let desc = unsafe { CStr::from_ptr(err.msg.as_ptr()) }
+            .to_string_lossy()
+            .into_owned();
+        Err(UclSchemaErrorType::from_code(err.code as i32, desc))
+    }
+
+    /// Iterate in reverse order. libUCL iterators are forward-only, so all items are collected first.
+    pub fn iter_rev(&self) -> impl DoubleEndedIterator<Item = ObjectRef> {
+        self.iter().collect::<Vec<ObjectRef>>().into_iter().rev()
+    }
+
+    /// Iterate over keys of an object. Yields nothing for other types.
+    pub fn keys(&self) -> impl Iterator<Item = String> + '_ {
+        self.iter().filter_map(|obj| obj.key())
+    }
+
+    /// Iterate over values of an object, same as `iter`.
+    pub fn values(&self) -> Iter<'_> {
+        self.iter()
+    }
+
+    /// Return a deep copy of this object with all keys recursively lowercased.
+    ///
+    /// Same as parsing with `ParserFlags::KEY_LOWERCASE`, but works on already parsed objects. Only ASCII characters are lowercased, just like libUCL does. Keys that become equal after lowercasing form an implicit array.
+    pub fn lowercase_keys(&self) -> Object {
+        match self.kind {
+            ucl_type_t::UCL_OBJECT | ucl_type_t::UCL_ARRAY => {
+                let ptr = unsafe { ucl_object_new_full(self.kind, self.priority().as_c_uint()) };
+                for child in self.iterate_raw(true) {
+                    // Every element of an implicit array needs its own copy.
+                    for elt in child.iterate_raw(false) {
+                        let copy = elt.lowercase_keys();
+                        let elt_ptr = copy.object;
+                        // Ownership is transferred to the container.
+                        std::mem::forget(copy);
+                        unsafe {
+                            if self.kind == ucl_type_t::UCL_OBJECT {
+                                let key = child.key().unwrap_or_default().to_ascii_lowercase();
+                                ucl_object_insert_key(
+                                    ptr,
+                                    elt_ptr,
+                                    key.as_ptr() as *const _,
+                                    key.len(),
+                                    true,
+                                );
+                            } else {
+                                ucl_array_append(ptr, elt_ptr);
+                            }
+                        }
+                    }
+                }
+                Object::from_c_ptr(ptr).expect("Got Object with null ptr")
+            }
+            _ => self.copy_single(),
+        }
+    }
+
+    /// Emit this object as human readable JSON. Works on any object, not just the root.
+    ///
+    /// Returns `None` if libUCL failed to emit the object.
+    pub fn emit_json(&self) -> Option<String> {
+        self.emit(ucl_emitter::UCL_EMIT_JSON)
+    }
+
+    /// Emit this object as compact JSON without any whitespace.
+    pub fn emit_json_compact(&self) -> Option<String> {
+        self.emit(ucl_emitter::UCL_EMIT_JSON_COMPACT)
+    }
+
+    /// Emit this object as YAML.
+    pub fn emit_yaml(&self) -> Option<String> {
+        self.emit(ucl_emitter::UCL_EMIT_YAML)
+    }
+
+    /// Emit this object in human readable UCL config form.
+    pub fn emit_config(&self) -> Option<String> {
+        self.emit(ucl_emitter::UCL_EMIT_CONFIG)
+    }
+
+    fn emit(&self, emit_type: ucl_emitter) -> Option<String> {
+        let ptr = unsafe { ucl_object_emit(self.object, emit_type) };
+        let result = utils::to_str(ptr as *const _);
+        // Emitted string is allocated by libUCL with malloc.
+        unsafe { libc::free(ptr.cast()) };
+        result
+    }
+
+    /// Iterate without crossing into other elements of an implicit array the way `Iter` does.
+    ///
+    /// With `expand_values` children of this object or array are returned, otherwise elements of the implicit array this object starts.
+    pub(crate) fn iterate_raw(&self, expand_values: bool) -> Vec<ObjectRef> {
+        let mut iter: ucl_object_iter_t = std::ptr::null_mut();
+        let mut result = Vec::new();
+        while let Some(obj) = ObjectRef::from_c_ptr(unsafe {
+            ucl_object_iterate_with_error(
+                self.object,
+                &mut iter,
+                expand_values,
+                std::ptr::null_mut(),
+            )
+        }) {
+            result.push(obj);
+        }
+        result
+    }
+
+    /// Elements of a sequence: values of the implicit array this object starts, with explicit arrays expanded.
+    ///
+    /// Unlike `Iter`, objects are not expanded into their children, so repeated blocks (`server {} server {}`) are elements themselves.
+    pub(crate) fn elements(&self) -> Vec<ObjectRef> {
+        self.iterate_raw(false)
+            .into_iter()
+            .flat_map(|obj| {
+                if obj.is_array() {
+                    obj.iterate_raw(true)
+                } else {
+                    vec![obj]
+                }
+            })
+            .collect()
+    }
+
+    /// Copy this object without siblings it may have in an implicit array.
+    ///
+    /// `ucl_object_copy` copies the whole implicit array, unless the object is an element of an explicit array.
+    fn copy_single(&self) -> Object {
+        unsafe {
+            let tmp = ucl_object_new_full(ucl_type_t::UCL_ARRAY, 0);
+            ucl_array_append(tmp, self.object);
+            let copy = ucl_object_copy(tmp);
+            // Take the original back before tmp is released, so it won't be unref'ed.
+            ucl_array_pop_last(tmp);
+            ucl_object_unref(tmp);
+            let ptr = ucl_array_pop_last(copy);
+            ucl_object_unref(copy);
+            Object::from_c_ptr(ptr).expect("Got Object with null ptr")
+        }
+    }
 }
 
 impl From<i64> for Object {
@@ -403,6 +1028,64 @@ impl From<bool> for Object {
         Object::from_c_ptr(ptr).expect("Failed to construct an object.")
     }
 }
+macro_rules! impl_from_int_for_object {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Object {
+                fn from(source: $t) -> Self {
+                    Object::from(i64::from(source))
+                }
+            }
+        )*
+    };
+}
+
+impl_from_int_for_object!(i8, i16, i32, u8, u16, u32);
+
+impl From<Duration> for Object {
+    /// Time object with the duration in seconds.
+    fn from(source: Duration) -> Self {
+        let cstring = utils::to_c_string(format!("{}s", source.as_secs_f64()));
+        let ptr = unsafe {
+            ucl_object_fromstring_common(
+                cstring.as_ptr(),
+                0,
+                ucl_string_flags::UCL_STRING_PARSE_TIME | ucl_string_flags::UCL_STRING_PARSE_DOUBLE,
+            )
+        };
+        Object::from_c_ptr(ptr).expect("Failed to construct an object.")
+    }
+}
+
+impl<T: Into<Object>> From<Option<T>> for Object {
+    /// `None` becomes `null`.
+    fn from(source: Option<T>) -> Self {
+        match source {
+            Some(value) => value.into(),
+            None => {
+                let ptr = unsafe { ucl_object_new_full(ucl_type_t::UCL_NULL, 0) };
+                Object::from_c_ptr(ptr).expect("Failed to construct an object.")
+            }
+        }
+    }
+}
+
+impl<T: Into<Object>> From<Vec<T>> for Object {
+    fn from(source: Vec<T>) -> Self {
+        let mut array = Object::new_array();
+        for value in source {
+            array.array_push(value.into());
+        }
+        array
+    }
+}
+
+impl From<String> for Object {
+    fn from(source: String) -> Self {
+        Object::from(source.as_str())
+    }
+}
+
 impl From<&str> for Object {
     fn from(source: &str) -> Self {
         let cstring = utils::to_c_string(source);
@@ -428,7 +1111,10 @@ impl FromObject<ObjectRef> for i64 {
 
 impl FromObject<ObjectRef> for u64 {
     fn try_from(value: ObjectRef) -> Result<Self, ObjectError> {
-        if let Some(val) = value.as_i64() {
+        if let Some(val) = value.as_u64() {
+            Ok(val)
+        } else if let Some(val) = value.as_i64() {
+            // Negative integer, report it as a failed conversion.
             val.try_into().map_err(ObjectError::from)
         } else {
             let err = ObjectError::WrongType {
@@ -531,9 +1217,28 @@ impl FromObject<ObjectRef> for u8 {
     }
 }
 
+impl FromObject<ObjectRef> for ByteSize {
+    fn try_from(value: ObjectRef) -> Result<Self, ObjectError> {
+        if let Some(val) = value.as_byte_size() {
+            Ok(val)
+        } else if let Some(val) = value.as_i64() {
+            // Negative integer, report it as a failed conversion.
+            let val: u64 = val.try_into().map_err(ObjectError::from)?;
+            Ok(ByteSize(val))
+        } else {
+            let err = ObjectError::WrongType {
+                key: value.key().unwrap_or_default(),
+                actual_type: value.kind,
+                wanted_type: ucl_type_t::UCL_INT,
+            };
+            Err(err)
+        }
+    }
+}
+
 impl FromObject<ObjectRef> for f64 {
     fn try_from(value: ObjectRef) -> Result<Self, ObjectError> {
-        if let Some(ret) = value.as_f64() {
+        if let Some(ret) = value.as_f64_or_int() {
             Ok(ret)
         } else {
             let err = ObjectError::WrongType {
@@ -591,6 +1296,82 @@ impl FromObject<ObjectRef> for String {
     }
 }
 
+impl FromObject<ObjectRef> for Arc<str> {
+    fn try_from(value: ObjectRef) -> Result<Self, ObjectError> {
+        if let Some(ret) = value.as_string() {
+            Ok(Arc::from(ret.as_str()))
+        } else {
+            let err = ObjectError::WrongType {
+                key: value.key().unwrap_or_default(),
+                actual_type: value.kind,
+                wanted_type: ucl_type_t::UCL_STRING,
+            };
+            Err(err)
+        }
+    }
+}
+
+impl FromObject<ObjectRef> for Rc<str> {
+    fn try_from(value: ObjectRef) -> Result<Self, ObjectError> {
+        if let Some(ret) = value.as_string() {
+            Ok(Rc::from(ret.as_str()))
+        } else {
+            let err = ObjectError::WrongType {
+                key: value.key().unwrap_or_default(),
+                actual_type: value.kind,
+                wanted_type: ucl_type_t::UCL_STRING,
+            };
+            Err(err)
+        }
+    }
+}
+
+impl FromObject<ObjectRef> for Cow<'static, str> {
+    fn try_from(value: ObjectRef) -> Result<Self, ObjectError> {
+        if let Some(ret) = value.as_string() {
+            Ok(Cow::Owned(ret))
+        } else {
+            let err = ObjectError::WrongType {
+                key: value.key().unwrap_or_default(),
+                actual_type: value.kind,
+                wanted_type: ucl_type_t::UCL_STRING,
+            };
+            Err(err)
+        }
+    }
+}
+
+#[cfg(feature = "compact_str")]
+impl FromObject<ObjectRef> for compact_str::CompactString {
+    fn try_from(value: ObjectRef) -> Result<Self, ObjectError> {
+        if let Some(ret) = value.as_string() {
+            Ok(ret.into())
+        } else {
+            let err = ObjectError::WrongType {
+                key: value.key().unwrap_or_default(),
+                actual_type: value.kind,
+                wanted_type: ucl_type_t::UCL_STRING,
+            };
+            Err(err)
+        }
+    }
+}
+
+impl FromObject<ObjectRef> for OsString {
+    fn try_from(value: ObjectRef) -> Result<Self, ObjectError> {
+        if let Some(ret) = value.as_string() {
+            Ok(ret.into())
+        } else {
+            let err = ObjectError::WrongType {
+                key: value.key().unwrap_or_default(),
+                actual_type: value.kind,
+                wanted_type: ucl_type_t::UCL_STRING,
+            };
+            Err(err)
+        }
+    }
+}
+
 impl FromObject<ObjectRef> for PathBuf {
     fn try_from(value: ObjectRef) -> Result<Self, ObjectError> {
         if let Some(ret) = value.as_string() {
@@ -625,16 +1406,108 @@ where
     T: FromObject<ObjectRef>,
 {
     fn try_from(value: ObjectRef) -> Result<Self, ObjectError> {
-        let ret = value
-            .iter()
-            .map(T::try_from)
-            .collect::<Vec<Result<T, ObjectError>>>();
-        if let Some(Err(err)) = ret.iter().find(|e| e.is_err()) {
-            Err(err.clone())
-        } else {
-            let list = ret.into_iter().filter_map(|e| e.ok()).collect();
-            Ok(list)
+        value.elements().into_iter().map(T::try_from).collect()
+    }
+}
+
+impl<T, S> FromObject<ObjectRef> for HashSet<T, S>
+where
+    T: FromObject<ObjectRef> + Eq + Hash,
+    S: BuildHasher + Default,
+{
+    fn try_from(value: ObjectRef) -> Result<Self, ObjectError> {
+        value.elements().into_iter().map(T::try_from).collect()
+    }
+}
+
+impl<T> FromObject<ObjectRef> for BTreeSet<T>
+where
+    T: FromObject<ObjectRef> + Ord,
+{
+    fn try_from(value: ObjectRef) -> Result<Self, ObjectError> {
+        value.elements().into_iter().map(T::try_from).collect()
+    }
+}
+
+macro_rules! impl_from_object_for_tuple {
+    ($len:expr => $($name:ident)+) => {
+        impl<$($name),+> FromObject<ObjectRef> for ($($name,)+)
+        where
+            $($name: FromObject<ObjectRef>),+
+        {
+            fn try_from(value: ObjectRef) -> Result<Self, ObjectError> {
+                if !value.is_array() {
+                    return Err(ObjectError::WrongType {
+                        key: value.key().unwrap_or_default(),
+                        actual_type: value.kind,
+                        wanted_type: ucl_type_t::UCL_ARRAY,
+                    });
+                }
+                let items: Vec<ObjectRef> = value.iter().collect();
+                if items.len() != $len {
+                    return Err(ObjectError::Other(format!(
+                        "Key \"{}\" has {} elements, but {} expected",
+                        value.key().unwrap_or_default(),
+                        items.len(),
+                        $len
+                    )));
+                }
+                let mut items = items.into_iter();
+                Ok(($($name::try_from(items.next().expect("length already checked"))?,)+))
+            }
+        }
+    };
+}
+
+impl_from_object_for_tuple!(2 => A B);
+impl_from_object_for_tuple!(3 => A B C);
+impl_from_object_for_tuple!(4 => A B C D);
+
+macro_rules! impl_from_object_for_non_zero {
+    ($($non_zero:ident => $int:ty),+) => {
+        $(
+        impl FromObject<ObjectRef> for $non_zero {
+            fn try_from(value: ObjectRef) -> Result<Self, ObjectError> {
+                if let Some(val) = value.as_i64() {
+                    let val: $int = val.try_into().map_err(ObjectError::from)?;
+                    $non_zero::new(val).ok_or_else(|| {
+                        ObjectError::Other(format!(
+                            "Key \"{}\" must not be zero",
+                            value.key().unwrap_or_default()
+                        ))
+                    })
+                } else {
+                    Err(ObjectError::WrongType {
+                        key: value.key().unwrap_or_default(),
+                        actual_type: value.kind,
+                        wanted_type: ucl_type_t::UCL_INT,
+                    })
+                }
+            }
         }
+        )+
+    };
+}
+
+impl_from_object_for_non_zero!(
+    NonZeroU8 => u8,
+    NonZeroU16 => u16,
+    NonZeroU32 => u32,
+    NonZeroU64 => u64,
+    NonZeroUsize => usize,
+    NonZeroI8 => i8,
+    NonZeroI16 => i16,
+    NonZeroI32 => i32,
+    NonZeroI64 => i64,
+    NonZeroIsize => isize
+);
+
+impl<T> FromObject<ObjectRef> for Box<T>
+where
+    T: FromObject<ObjectRef>,
+{
+    fn try_from(value: ObjectRef) -> Result<Self, ObjectError> {
+        T::try_from(value).map(Box::new)
     }
 }
 
@@ -643,13 +1516,16 @@ where
     T: FromObject<ObjectRef>,
 {
     fn try_from(value: ObjectRef) -> Result<Self, ObjectError> {
+        if value.is_null() {
+            return Ok(None);
+        }
         (T::try_from(value)).map(Some)
     }
 }
 
 impl<T, S> FromObject<ObjectRef> for HashMap<String, T, S>
 where
-    T: FromObject<ObjectRef> + Clone,
+    T: FromObject<ObjectRef>,
     S: BuildHasher + Default,
 {
     fn try_from(value: ObjectRef) -> Result<Self, ObjectError> {
@@ -660,32 +1536,66 @@ where
                 wanted_type: ucl_type_t::UCL_OBJECT,
             });
         }
-        let as_entries: Vec<(String, Result<T, ObjectError>)> = value
+        value
             .iter()
             .map(|obj| {
-                (
-                    obj.key().expect("Object without key!"),
-                    FromObject::try_from(obj),
-                )
+                let key = obj.key().expect("Object without key!");
+                Ok((key, T::try_from(obj)?))
             })
-            .collect();
+            .collect()
+    }
+}
 
-        if let Some((_, Err(e))) = as_entries.iter().find(|(_key, result)| result.is_err()) {
-            Err(e.clone())
-        } else {
-            Ok(as_entries
-                .iter()
-                .cloned()
-                .map(|(key, result)| (key, result.unwrap()))
-                .collect())
+impl<T> FromObject<ObjectRef> for BTreeMap<String, T>
+where
+    T: FromObject<ObjectRef>,
+{
+    fn try_from(value: ObjectRef) -> Result<Self, ObjectError> {
+        if ucl_type_t::UCL_OBJECT != value.kind {
+            return Err(ObjectError::WrongType {
+                key: value.key().unwrap_or_default(),
+                actual_type: value.kind,
+                wanted_type: ucl_type_t::UCL_OBJECT,
+            });
         }
+        let mut map = BTreeMap::new();
+        for obj in value.iter() {
+            let key = obj.key().expect("Object without key!");
+            map.insert(key, T::try_from(obj)?);
+        }
+        Ok(map)
     }
 }
 
+/// Plain numbers are treated as seconds. Negative durations are rejected.
 impl FromObject<ObjectRef> for Duration {
     fn try_from(value: ObjectRef) -> Result<Self, ObjectError> {
-        if let Some(seconds) = value.as_time() {
-            Ok(Duration::from_secs_f64(seconds))
+        if let Some(seconds) = value.as_time().or_else(|| value.as_f64_or_int()) {
+            Duration::try_from_secs_f64(seconds).map_err(ObjectError::other)
+        } else {
+            Err(ObjectError::WrongType {
+                key: value.key().unwrap_or_default(),
+                actual_type: value.kind,
+                wanted_type: ucl_type_t::UCL_TIME,
+            })
+        }
+    }
+}
+
+/// Time is converted with millisecond precision, anything smaller is truncated. Plain numbers are treated as seconds.
+#[cfg(feature = "chrono")]
+impl FromObject<ObjectRef> for chrono::Duration {
+    fn try_from(value: ObjectRef) -> Result<Self, ObjectError> {
+        if let Some(seconds) = value.as_time().or_else(|| value.as_f64_or_int()) {
+            let millis = seconds * 1000.0;
+            if !millis.is_finite() || millis.abs() >= i64::MAX as f64 {
+                return Err(ObjectError::other(format!(
+                    "{} seconds is out of range",
+                    seconds
+                )));
+            }
+            chrono::Duration::try_milliseconds(millis as i64)
+                .ok_or_else(|| ObjectError::other(format!("{} seconds is out of range", seconds)))
         } else {
             Err(ObjectError::WrongType {
                 key: value.key().unwrap_or_default(),
@@ -723,6 +1633,34 @@ impl PartialEq for ObjectRef {
     }
 }
 
+impl Hash for ObjectRef {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (self.kind as u32).hash(state);
+        match self.kind {
+            ucl_type_t::UCL_STRING => self.as_string().hash(state),
+            // Integers are compared as doubles.
+            ucl_type_t::UCL_INT => self.as_i64().map(|v| (v as f64).to_bits()).hash(state),
+            ucl_type_t::UCL_BOOLEAN => self.as_bool().hash(state),
+            // libUCL compares arrays and objects by length first, equal objects have the same keys.
+            ucl_type_t::UCL_ARRAY => {
+                let elements = self.iterate_raw(true);
+                elements.len().hash(state);
+                for element in elements {
+                    element.hash(state);
+                }
+            }
+            ucl_type_t::UCL_OBJECT => self.iterate_raw(true).len().hash(state),
+            _ => {}
+        }
+    }
+}
+
+impl Hash for Object {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_ref().hash(state)
+    }
+}
+
 impl PartialEq for Object {
     fn eq(&self, other: &Self) -> bool {
         self.as_ref() == other.as_ref()
@@ -738,37 +1676,44 @@ impl Clone for Object {
 
 impl PartialOrd for ObjectRef {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        let cmp = unsafe { ucl_object_compare(self.as_ptr(), other.as_ptr()) };
-        match cmp {
-            cmp if cmp == 0 => Some(Ordering::Equal),
-            cmp if cmp < 0 => Some(Ordering::Less),
-            cmp if cmp > 0 => Some(Ordering::Greater),
-            _ => unreachable!(),
-        }
+        Some(self.cmp(other))
     }
 }
 
 impl Ord for ObjectRef {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.partial_cmp(other).unwrap()
+        let cmp = unsafe { ucl_object_compare(self.as_ptr(), other.as_ptr()) };
+        cmp.cmp(&0)
     }
 }
 
 impl PartialOrd for Object {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.as_ref().partial_cmp(other.as_ref())
+        Some(self.cmp(other))
     }
 }
 
 impl Ord for Object {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.partial_cmp(other).unwrap()
+        self.as_ref().cmp(other.as_ref())
     }
 }
 #[cfg(test)]
 mod test {
     use super::*;
 
+    fn parse(input: &str) -> Object {
+        let mut parser = crate::Parser::default();
+        parser
+            .add_chunk_full(
+                input,
+                Priority::default(),
+                crate::DEFAULT_DUPLICATE_STRATEGY,
+            )
+            .unwrap();
+        parser.get_object().unwrap()
+    }
+
     #[test]
     fn eq() {
         let left = Object::from(1);
@@ -787,6 +1732,601 @@ mod test {
         assert_ne!(left.as_ptr(), right.as_ptr());
     }
 
+    #[test]
+    fn keys_and_values() {
+        let root = parse("dict { a = 1, b = 2 }\nlist = [1, 2]");
+        let dict = root.lookup("dict").unwrap();
+
+        assert_eq!(vec!["a", "b"], dict.keys().collect::<Vec<String>>());
+        let values: Vec<i64> = dict.values().filter_map(|obj| obj.as_i64()).collect();
+        assert_eq!(vec![1, 2], values);
+        assert_eq!(0, root.lookup("list").unwrap().keys().count());
+    }
+
+    #[test]
+    fn iter_rev() {
+        let root = parse("dict { a = 1, b = 2, c = 3 }\nlist = [1, 2, 3]");
+
+        let keys: Vec<String> = root
+            .lookup("dict")
+            .unwrap()
+            .iter_rev()
+            .filter_map(|obj| obj.key())
+            .collect();
+        assert_eq!(vec!["c", "b", "a"], keys);
+        let values: Vec<i64> = root
+            .lookup("list")
+            .unwrap()
+            .iter_rev()
+            .filter_map(|obj| obj.as_i64())
+            .collect();
+        assert_eq!(vec![3, 2, 1], values);
+    }
+
+    #[test]
+    fn to_vec_indexed() {
+        let root = parse("ports = [80, 443, \"http\", 8080]\nok = [1, 2]");
+
+        let ok: Vec<i64> = root.lookup("ok").unwrap().to_vec_indexed().unwrap();
+        assert_eq!(vec![1, 2], ok);
+        let (idx, err) = root
+            .lookup("ports")
+            .unwrap()
+            .to_vec_indexed::<i64>()
+            .unwrap_err();
+        assert_eq!(2, idx);
+        assert!(matches!(err, ObjectError::WrongType { .. }));
+    }
+
+    #[test]
+    fn iter_as() {
+        let root = parse("items = [1, \"two\", 3]");
+        let items = root.lookup("items").unwrap();
+
+        let lenient: Vec<i64> = items.iter_as().collect();
+        assert_eq!(vec![1, 3], lenient);
+        let strings: Vec<String> = items.iter_as().collect();
+        assert_eq!(vec!["two".to_string()], strings);
+        let err = items.try_collect_as::<i64>().unwrap_err();
+        assert!(matches!(err, ObjectError::WrongType { .. }));
+    }
+
+    #[test]
+    fn hash_set_of_objects() {
+        let root =
+            parse("a = [1, \"two\", { x = 1 }]\nb = [1, \"two\", { x = 1 }]\nc = [1, 2]\nd = 1.5");
+
+        let mut set = HashSet::new();
+        set.insert(root.lookup("a").unwrap());
+        set.insert(root.lookup("b").unwrap());
+        assert_eq!(1, set.len());
+        set.insert(root.lookup("c").unwrap());
+        set.insert(root.lookup("d").unwrap());
+        assert_eq!(3, set.len());
+    }
+
+    #[test]
+    fn into_io_error() {
+        let err: std::io::Error = ObjectError::KeyNotFound("port".to_string()).into();
+        assert_eq!(std::io::ErrorKind::InvalidData, err.kind());
+        assert_eq!("Key \"port\" not found in the object", err.to_string());
+    }
+
+    #[test]
+    fn object_from_values() {
+        assert_eq!(Some(8080), Object::from(8080u16).as_i64());
+        assert_eq!(Some(-1), Object::from(-1i8).as_i64());
+
+        let none: Option<String> = None;
+        assert!(Object::from(none).is_null());
+        assert_eq!(
+            Some("web".to_string()),
+            Object::from(Some("web")).as_string()
+        );
+
+        let list: Vec<u32> = Object::from(vec![1u32, 2]).try_collect_as().unwrap();
+        assert_eq!(vec![1, 2], list);
+
+        let timeout = Object::from(Duration::from_millis(1500));
+        assert!(timeout.is_time());
+        assert_eq!(Some(1.5), timeout.as_time());
+    }
+
+    #[test]
+    fn build_object() {
+        let mut list = Object::new_array();
+        assert!(list.array_push(Object::from(1)));
+        assert!(list.array_push(Object::from(2)));
+        assert!(!list.insert("a", Object::from(1)));
+
+        let mut root = Object::new_object();
+        assert!(root.insert("a", Object::from(1)));
+        assert!(root.insert("list", list));
+        assert!(!root.array_push(Object::from(3)));
+
+        assert_eq!(Some(1), root.lookup("a").unwrap().as_i64());
+        let list: Vec<i64> = root.lookup("list").unwrap().try_collect_as().unwrap();
+        assert_eq!(vec![1, 2], list);
+        assert_eq!(2, root.iter().len());
+
+        assert!(root.replace("a", Object::from(5)));
+        assert!(root.replace("b", Object::from(6)));
+        assert!(!Object::new_array().replace("a", Object::from(1)));
+        assert_eq!(Some(5), root.lookup("a").unwrap().as_i64());
+        assert_eq!(Some(6), root.lookup("b").unwrap().as_i64());
+        assert_eq!(3, root.iter().len());
+    }
+
+    #[test]
+    fn merge_objects() {
+        let parse_with = |input: &str, priority: u32| {
+            let mut parser = crate::Parser::default();
+            parser
+                .add_chunk_full(
+                    input,
+                    Priority::new(priority),
+                    crate::DEFAULT_DUPLICATE_STRATEGY,
+                )
+                .unwrap();
+            parser.get_object().unwrap()
+        };
+        let defaults = "a = 1\nnested { x = 1 }\nlist = [1]";
+        let overrides = "a = 2\nb = 3\nnested { y = 2 }\nlist = [2]";
+
+        let mut root = parse(defaults);
+        root.merge(parse(overrides), DuplicateStrategy::UCL_DUPLICATE_MERGE)
+            .unwrap();
+        let a: Vec<i64> = root.lookup("a").unwrap().try_collect_as().unwrap();
+        assert_eq!(vec![1, 2], a);
+        assert_eq!(Some(3), root.lookup("b").unwrap().as_i64());
+        assert_eq!(Some(1), root.lookup_path("nested.x").unwrap().as_i64());
+        assert_eq!(Some(2), root.lookup_path("nested.y").unwrap().as_i64());
+        let list: Vec<i64> = root.lookup("list").unwrap().try_collect_as().unwrap();
+        assert_eq!(vec![1, 2], list);
+
+        let mut root = parse(defaults);
+        root.merge(parse(overrides), DuplicateStrategy::UCL_DUPLICATE_REWRITE)
+            .unwrap();
+        assert_eq!(Some(2), root.lookup("a").unwrap().as_i64());
+        assert_eq!(1, root.lookup("a").unwrap().iter().len());
+        assert!(root.lookup_path("nested.x").is_none());
+
+        let mut root = parse(defaults);
+        root.merge(
+            parse_with("a = 3", 5),
+            DuplicateStrategy::UCL_DUPLICATE_APPEND,
+        )
+        .unwrap();
+        assert_eq!(Some(3), root.lookup("a").unwrap().as_i64());
+        assert_eq!(1, root.lookup("a").unwrap().iter().len());
+
+        let mut root = parse(defaults);
+        let err = root
+            .merge(parse(overrides), DuplicateStrategy::UCL_DUPLICATE_ERROR)
+            .unwrap_err();
+        assert!(matches!(err, ObjectError::Other(_)));
+
+        let mut root = parse(defaults);
+        let err = root
+            .merge(Object::from(1), DuplicateStrategy::UCL_DUPLICATE_MERGE)
+            .unwrap_err();
+        assert!(matches!(err, ObjectError::WrongType { .. }));
+    }
+
+    #[test]
+    fn validate_schema() {
+        let schema = parse(
+            r#"
+            type = object
+            properties { port { type = integer, minimum = 1 } }
+            required = [port]
+            "#,
+        );
+
+        assert_eq!(Ok(()), parse("port = 80").validate(&schema));
+
+        let err = parse("host = localhost").validate(&schema).unwrap_err();
+        assert_eq!(UclSchemaErrorType::MissingProperty, err.code);
+        let err = parse("port = 0").validate(&schema).unwrap_err();
+        assert_eq!(UclSchemaErrorType::Constraint, err.code);
+        let err = parse("port = http").validate(&schema).unwrap_err();
+        assert_eq!(UclSchemaErrorType::TypeMismatch, err.code);
+        assert!(!err.desc.is_empty());
+    }
+
+    #[test]
+    fn is_scalar() {
+        let root = parse(
+            r#"
+                int = 1
+                float = 1.5
+                string = "a"
+                boolean = true
+                time = 10s
+                null = null
+                object {}
+                array = []
+                "#,
+        );
+
+        for key in &["int", "float", "string", "boolean", "time", "null"] {
+            assert!(root.lookup(key).unwrap().is_scalar(), "{}", key);
+        }
+        for key in &["object", "array"] {
+            assert!(!root.lookup(key).unwrap().is_scalar(), "{}", key);
+        }
+        let userdata =
+            Object::from_c_ptr(unsafe { ucl_object_new_full(ucl_type_t::UCL_USERDATA, 0) })
+                .unwrap();
+        assert!(!userdata.is_scalar());
+    }
+
+    #[test]
+    fn as_object() {
+        let root = parse(
+            r#"
+                dict {
+                    name = "web"
+                    port = 8080
+                    enabled = yes
+                    tags = a
+                    tags = b
+                }
+                list = [1, 2]
+                "#,
+        );
+
+        let dict = root.lookup("dict").unwrap().as_object().unwrap();
+        let mut keys: Vec<&String> = dict.keys().collect();
+        keys.sort();
+        assert_eq!(vec!["enabled", "name", "port", "tags"], keys);
+        assert_eq!(Some("web".to_string()), dict["name"].as_string());
+        assert_eq!(Some(8080), dict["port"].as_i64());
+        assert_eq!(Some(true), dict["enabled"].as_bool());
+        assert_eq!(2, dict["tags"].iter().count());
+
+        assert!(root.lookup("list").unwrap().as_object().is_none());
+    }
+
+    #[test]
+    fn type_name() {
+        let root = parse(
+            r#"
+                object {}
+                array = []
+                int = 1
+                float = 1.5
+                string = "a"
+                boolean = true
+                time = 10s
+                null = null
+                "#,
+        );
+
+        for name in &[
+            "object", "array", "int", "float", "string", "boolean", "time", "null",
+        ] {
+            assert_eq!(*name, root.lookup(name).unwrap().type_name());
+        }
+        let userdata =
+            Object::from_c_ptr(unsafe { ucl_object_new_full(ucl_type_t::UCL_USERDATA, 0) })
+                .unwrap();
+        assert_eq!("userdata", userdata.type_name());
+
+        let err: ObjectError =
+            <i64 as FromObject<ObjectRef>>::try_from(root.lookup("string").unwrap()).unwrap_err();
+        assert_eq!(
+            "Key \"string\" actual type is string and not int",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn lookup_path_sep() {
+        let root = parse(r#"domains { "example.com" { port = 443 } }"#);
+
+        assert!(root.lookup_path("domains.example.com.port").is_none());
+        assert_eq!(
+            Some(443),
+            root.lookup_path_sep("domains/example.com/port", '/')
+                .and_then(|obj| obj.as_i64())
+        );
+        assert_eq!(
+            Some(443),
+            root.lookup_path_bytes_sep(b"domains/example.com/port\0", b'/')
+                .and_then(|obj| obj.as_i64())
+        );
+        assert!(root
+            .lookup_path_sep("domains/example.com/port", 'é')
+            .is_none());
+    }
+
+    #[test]
+    fn try_lookup() {
+        let root = parse("server { port = 80 }");
+
+        let port = root
+            .try_lookup("server")
+            .and_then(|server| server.try_lookup("port"))
+            .unwrap();
+        assert_eq!(Some(80), port.as_i64());
+        assert_eq!(
+            ObjectError::KeyNotFound("host".to_string()),
+            root.try_lookup("server")
+                .and_then(|server| server.try_lookup("host"))
+                .unwrap_err()
+        );
+        assert_eq!(
+            ObjectError::WrongType {
+                key: "port".to_string(),
+                actual_type: ucl_type_t::UCL_INT,
+                wanted_type: ucl_type_t::UCL_OBJECT,
+            },
+            port.try_lookup("number").unwrap_err()
+        );
+    }
+
+    #[test]
+    fn shell_quoted() {
+        let root = parse(
+            r#"
+                plain = "ls"
+                spaces = "my file.txt"
+                quotes = "it's $HOME"
+                number = 1
+                "#,
+        );
+
+        let quoted = |key| root.lookup(key).unwrap().as_shell_quoted();
+        assert_eq!(Some("'ls'".to_string()), quoted("plain"));
+        assert_eq!(Some("'my file.txt'".to_string()), quoted("spaces"));
+        assert_eq!(Some(r#"'it'\''s $HOME'"#.to_string()), quoted("quotes"));
+        assert_eq!(None, quoted("number"));
+    }
+
+    #[test]
+    fn get() {
+        let root = parse("server { port = 80 }");
+
+        let port = root.get("server").and_then(|server| server.get("port"));
+        assert_eq!(Some(80), port.and_then(|port| port.as_i64()));
+        assert!(root.get("missing").is_none());
+        assert!(root.get("server").unwrap().get("missing").is_none());
+    }
+
+    #[test]
+    fn lookup_owned() {
+        let mut parser = crate::Parser::default();
+        parser
+            .add_chunk_full(
+                "server { listen { port = 80 } }",
+                Priority::default(),
+                crate::DEFAULT_DUPLICATE_STRATEGY,
+            )
+            .unwrap();
+        let root = parser.get_object().unwrap();
+        let server = root.lookup_owned("server").unwrap();
+        let port = root.lookup_path_owned("server.listen.port").unwrap();
+        assert!(root.lookup_owned("missing").is_none());
+        drop(root);
+        drop(parser);
+
+        assert_eq!(
+            Some(80),
+            server.lookup_path("listen.port").unwrap().as_i64()
+        );
+        assert_eq!(Some(80), port.as_i64());
+    }
+
+    #[test]
+    fn lookup_path_bytes() {
+        let root = parse("section { key = 1 }");
+
+        let expected = root.lookup_path("section.key");
+        assert_eq!(
+            Some(1),
+            root.lookup_path_bytes(b"section.key\0").unwrap().as_i64()
+        );
+        assert_eq!(expected, root.lookup_path_bytes(b"section.key\0"));
+        assert!(root.lookup_path_bytes(b"section.missing\0").is_none());
+        assert!(root.lookup_path_bytes(b"section.key").is_none());
+        assert!(root.lookup_path_bytes(b"section\0.key\0").is_none());
+    }
+
+    #[test]
+    fn non_zero_from_object() {
+        let root = parse("workers = 4\nidle = 0\nnegative = -1");
+
+        let workers: NonZeroU32 = FromObject::try_from(root.lookup("workers").unwrap()).unwrap();
+        assert_eq!(4, workers.get());
+        let workers: NonZeroUsize = FromObject::try_from(root.lookup("workers").unwrap()).unwrap();
+        assert_eq!(4, workers.get());
+        let idle: Result<NonZeroU32, ObjectError> =
+            FromObject::try_from(root.lookup("idle").unwrap());
+        assert_eq!(
+            Err(ObjectError::Other(
+                "Key \"idle\" must not be zero".to_string()
+            )),
+            idle
+        );
+        let negative: Result<NonZeroU32, ObjectError> =
+            FromObject::try_from(root.lookup("negative").unwrap());
+        assert!(matches!(negative, Err(ObjectError::IntConversionError(_))));
+        let negative: NonZeroI64 = FromObject::try_from(root.lookup("negative").unwrap()).unwrap();
+        assert_eq!(-1, negative.get());
+    }
+
+    #[test]
+    fn duration_from_object() {
+        let root = parse("int = 30\nfloat = 1.5\ntime = 10ms\nnegative = -1\nname = fast");
+        let duration = |key: &str| -> Result<Duration, ObjectError> {
+            FromObject::try_from(root.lookup(key).unwrap())
+        };
+
+        assert_eq!(Ok(Duration::from_secs(30)), duration("int"));
+        assert_eq!(Ok(Duration::from_millis(1500)), duration("float"));
+        assert_eq!(Ok(Duration::from_millis(10)), duration("time"));
+        assert!(duration("negative").is_err());
+        assert!(duration("name").is_err());
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono_duration_from_object() {
+        let root =
+            parse("timeout = 1500ms\ninterval = 10ms\nseconds = 2\nhuge = 1e300\nname = fast");
+
+        let timeout: chrono::Duration =
+            FromObject::try_from(root.lookup("timeout").unwrap()).unwrap();
+        assert_eq!(chrono::Duration::milliseconds(1500), timeout);
+        let interval: chrono::Duration =
+            FromObject::try_from(root.lookup("interval").unwrap()).unwrap();
+        assert_eq!(chrono::Duration::milliseconds(10), interval);
+        let seconds: chrono::Duration =
+            FromObject::try_from(root.lookup("seconds").unwrap()).unwrap();
+        assert_eq!(chrono::Duration::seconds(2), seconds);
+        let huge: Result<chrono::Duration, ObjectError> =
+            FromObject::try_from(root.lookup("huge").unwrap());
+        assert!(matches!(huge, Err(ObjectError::Other(_))));
+        let name: Result<chrono::Duration, ObjectError> =
+            FromObject::try_from(root.lookup("name").unwrap());
+        assert!(name.is_err());
+    }
+
+    #[cfg(feature = "compact_str")]
+    #[test]
+    fn compact_string_from_object() {
+        use compact_str::CompactString;
+
+        let root = parse("name = short\nport = 1");
+
+        let actual: CompactString = FromObject::try_from(root.lookup("name").unwrap()).unwrap();
+        assert_eq!("short", actual);
+
+        let actual: Result<CompactString, ObjectError> =
+            FromObject::try_from(root.lookup("port").unwrap());
+        assert!(actual.is_err());
+    }
+
+    #[test]
+    fn entry() {
+        let root = parse("a = 1\nb = two");
+
+        let entries: Vec<(Option<String>, ObjectRef)> = root.iter().map(|o| o.entry()).collect();
+        assert_eq!(2, entries.len());
+        assert_eq!(Some("a".to_string()), entries[0].0);
+        assert_eq!(Some(1), entries[0].1.as_i64());
+        assert_eq!(Some("b".to_string()), entries[1].0);
+        assert_eq!(Some("two".to_string()), entries[1].1.as_string());
+    }
+
+    #[test]
+    fn byte_size() {
+        let root = parse("buffer = 1mb\nsmall = 1536\nnegative = -1\nname = big");
+
+        let buffer = root.lookup("buffer").unwrap().as_byte_size().unwrap();
+        assert_eq!(ByteSize(1024 * 1024), buffer);
+        assert_eq!(1024, buffer.as_kib());
+        assert_eq!(1, buffer.as_mib());
+
+        let small: ByteSize = FromObject::try_from(root.lookup("small").unwrap()).unwrap();
+        assert_eq!(1536, small.as_bytes());
+        assert_eq!(1, small.as_kib());
+        assert_eq!(0, small.as_mib());
+
+        assert!(root.lookup("negative").unwrap().as_byte_size().is_none());
+        let negative: Result<ByteSize, ObjectError> =
+            FromObject::try_from(root.lookup("negative").unwrap());
+        assert!(negative.is_err());
+        assert!(root.lookup("name").unwrap().as_byte_size().is_none());
+    }
+
+    #[test]
+    fn lowercase_keys() {
+        let root = parse("Server { Listen = 80\nListen = 443\nTags = [{ Name = web }] }\nServer { Listen = 8080 }\nPath = \"/Var\"");
+        let lowered = root.lowercase_keys();
+
+        assert!(lowered.lookup("Server").is_none());
+        assert_eq!(
+            Some("/Var".to_string()),
+            lowered.lookup("path").unwrap().as_string()
+        );
+        let listen: Vec<i64> = lowered
+            .lookup_path("server.listen")
+            .unwrap()
+            .iter()
+            .map(|obj| obj.as_i64().unwrap())
+            .collect();
+        assert_eq!(vec![80, 443], listen);
+        let tags = lowered.lookup_path("server.tags").unwrap();
+        let tag = tags.iter().next().unwrap();
+        assert_eq!(
+            Some("web".to_string()),
+            tag.lookup("name").unwrap().as_string()
+        );
+
+        let servers = root.lookup("Server").unwrap().iterate_raw(false).len();
+        let lowered_servers = lowered.lookup("server").unwrap().iterate_raw(false);
+        assert_eq!(servers, lowered_servers.len());
+        assert_eq!(
+            Some(8080),
+            lowered_servers
+                .last()
+                .unwrap()
+                .lookup("listen")
+                .unwrap()
+                .as_i64()
+        );
+
+        // Source is left untouched.
+        assert!(root.lookup_path("Server.Listen").is_some());
+    }
+
+    #[test]
+    fn as_u64() {
+        let root = parse("count = 5\nnegative = -5\nname = five");
+
+        assert_eq!(Some(5), root.lookup("count").unwrap().as_u64());
+        assert_eq!(None, root.lookup("negative").unwrap().as_u64());
+        assert_eq!(None, root.lookup("name").unwrap().as_u64());
+
+        let count: u64 = FromObject::try_from(root.lookup("count").unwrap()).unwrap();
+        assert_eq!(5, count);
+        let negative: Result<u64, ObjectError> =
+            FromObject::try_from(root.lookup("negative").unwrap());
+        assert!(matches!(negative, Err(ObjectError::IntConversionError(_))));
+    }
+
+    #[test]
+    fn as_f64_or_int() {
+        let root = parse("threshold = 5\nratio = 0.5\nname = five");
+
+        let threshold = root.lookup("threshold").unwrap();
+        assert!(!threshold.is_float());
+        assert_eq!(None, threshold.as_f64());
+        assert_eq!(Some(5.0), threshold.as_f64_or_int());
+        assert_eq!(Some(0.5), root.lookup("ratio").unwrap().as_f64_or_int());
+        assert_eq!(None, root.lookup("name").unwrap().as_f64_or_int());
+
+        let threshold: f64 = FromObject::try_from(threshold).unwrap();
+        assert_eq!(5.0, threshold);
+    }
+
+    #[test]
+    fn priority() {
+        let mut obj = Object::from(42i64);
+        assert_eq!(Priority::default(), obj.priority());
+
+        let copy = obj.with_priority(Priority::new(3));
+        assert_eq!(Priority::new(3), copy.priority());
+        assert_eq!(Priority::default(), obj.priority());
+        assert_eq!(Some(42), copy.as_i64());
+
+        obj.set_priority(Priority::new(7));
+        assert_eq!(Priority::new(7), obj.priority());
+        assert_eq!(7, obj.priority().as_c_uint());
+    }
+
     #[test]
     fn order_good() {
         let left = Object::from(1);
@@ -810,4 +2350,27 @@ mod test {
 
         assert_ne!(left, right);
     }
+
+    #[test]
+    fn emit_round_trip() {
+        let input = r#"
+        name = "web"
+        upstream {
+            hosts = ["a", "b"]
+            port = 8080
+            enabled = yes
+        }
+        "#;
+        let root = parse(input);
+
+        let emitted = vec![
+            root.emit_json().unwrap(),
+            root.emit_json_compact().unwrap(),
+            root.emit_config().unwrap(),
+            root.emit_yaml().unwrap(),
+        ];
+        for output in emitted {
+            assert_eq!(root, parse(&output), "{}", output);
+        }
+    }
 }
@@ -16,10 +16,11 @@
 //! ```
 use crate::raw::{DuplicateStrategy, Priority};
 use libucl_bind::{
-    ucl_parse_type, ucl_parser, ucl_parser_add_chunk_full, ucl_parser_add_fd_full,
-    ucl_parser_add_file_full, ucl_parser_free, ucl_parser_get_error, ucl_parser_get_error_code,
-    ucl_parser_get_object, ucl_parser_new, ucl_parser_register_variable, ucl_parser_set_filevars,
-    ucl_parser_set_variables_handler, ucl_variable_handler,
+    ucl_macro_handler, ucl_parse_type, ucl_parser, ucl_parser_add_chunk_full,
+    ucl_parser_add_fd_full, ucl_parser_add_file_full, ucl_parser_free, ucl_parser_get_column,
+    ucl_parser_get_error, ucl_parser_get_error_code, ucl_parser_get_linenum, ucl_parser_get_object,
+    ucl_parser_new, ucl_parser_register_macro, ucl_parser_register_variable,
+    ucl_parser_set_filevars, ucl_parser_set_variables_handler, ucl_variable_handler,
 };
 
 #[cfg(unix)]
@@ -27,9 +28,12 @@ use std::os::unix::io::AsRawFd;
 
 use super::{utils, ParserFlags, DEFAULT_PARSER_FLAG};
 use crate::error;
-use crate::raw::object::Object;
-use crate::traits::VariableHandler;
+use crate::raw::object::{Object, ObjectRef};
+use crate::traits::{MacroHandler, VariableHandler};
+use std::collections::{BTreeMap, HashMap};
+use std::ffi::CString;
 use std::fmt;
+use std::os::raw::c_uint;
 use std::path::Path;
 
 /// Raw parser object.
@@ -37,6 +41,13 @@ pub struct Parser {
     parser: *mut ucl_parser,
     flags: ParserFlags,
     var_handler: Option<Box<dyn VariableHandler>>,
+    /// Variables registered with `register_variable`, so they can be registered with a check parser in `add_chunks_best_effort`.
+    variables: HashMap<String, String>,
+    macro_handlers: Vec<Box<dyn MacroHandler>>,
+    /// Every macro registered with libUCL, so it can be registered with a check parser in `add_chunks_best_effort`.
+    macros: Vec<(CString, ucl_macro_handler, *mut std::ffi::c_void)>,
+    /// Handler registered with `set_variables_handler_raw`.
+    raw_var_handler: (ucl_variable_handler, *mut std::ffi::c_void),
 }
 
 impl Default for Parser {
@@ -46,25 +57,68 @@ impl Default for Parser {
 }
 
 impl Parser {
-    fn get_error(&mut self) -> error::UclError {
+    fn get_error(&self) -> error::UclError {
         let err = unsafe { ucl_parser_get_error_code(self.parser) };
         let desc = unsafe { ucl_parser_get_error(self.parser) };
+        // libUCL returns 0 when there is no chunk to take position from.
+        let line = unsafe { ucl_parser_get_linenum(self.parser) } as usize;
+        let column = unsafe { ucl_parser_get_column(self.parser) } as usize;
 
-        error::UclErrorType::from_code(err, utils::to_str(desc).unwrap())
+        error::UclErrorType::from_code(
+            err,
+            utils::to_str(desc).unwrap_or_else(|| "no error description".to_string()),
+        )
+        .with_position(
+            Some(line).filter(|l| *l > 0),
+            Some(column).filter(|c| *c > 0),
+        )
     }
 
     /// Create a new parser with given option flags.
+    ///
+    /// Bits that are not named constants of `ParserFlags` are removed. libUCL's zero-copy mode is one of them: in that mode objects point into the input, and nothing makes the input outlive them.
     pub fn with_flags(flags: ParserFlags) -> Self {
+        let flags = ParserFlags::from_bits_truncate(flags.bits());
         Parser {
-            parser: unsafe { ucl_parser_new(flags.0 as i32) },
+            parser: unsafe { ucl_parser_new(flags.bits() as i32) },
             flags,
             var_handler: None,
+            variables: HashMap::new(),
+            macro_handlers: Vec::new(),
+            macros: Vec::new(),
+            raw_var_handler: (None, std::ptr::null_mut()),
         }
     }
 
+    /// Create a new parser with default flags and `ParserFlags::NO_FILEVARS`: `$FILENAME` and `$CURDIR` are never defined.
+    ///
+    /// Calls to `set_filevars` are ignored by such parser, and files are read with `add_file_copy`, because libUCL defines file variables for every file it reads.
+    pub fn without_filevars() -> Self {
+        Self::with_flags(DEFAULT_PARSER_FLAG | ParserFlags::NO_FILEVARS)
+    }
+
+    /// Replace the inner parser with a new one that has the same flags.
+    ///
+    /// libUCL can't reset a parser, so the old one is freed. Variables (including `$FILENAME` and `$CURDIR`), variable handler and macros are gone with it and need to be registered again. Objects returned by `get_object` before the reset stay valid.
+    pub fn reset(&mut self) {
+        unsafe { ucl_parser_free(self.parser) };
+        self.parser = unsafe { ucl_parser_new(self.flags.bits() as i32) };
+        self.var_handler = None;
+        self.variables.clear();
+        self.macro_handlers.clear();
+        self.macros.clear();
+        self.raw_var_handler = (None, std::ptr::null_mut());
+    }
+
+    fn filevars_disabled(&self) -> bool {
+        self.flags.contains(ParserFlags::NO_FILEVARS)
+    }
+
     /// Add a chunk of text to the parser. String must:
     /// - not have `\0` character;
     /// - must be valid UCL object;
+    ///
+    /// Chunk is copied during the call, so it doesn't need to outlive it.
     pub fn add_chunk_full<C: AsRef<str>>(
         &mut self,
         chunk: C,
@@ -76,7 +130,7 @@ impl Parser {
             ucl_parser_add_chunk_full(
                 self.parser,
                 chunk.as_ptr(),
-                chunk.as_bytes().len(),
+                chunk.len(),
                 priority.as_c_uint(),
                 strategy,
                 ucl_parse_type::UCL_PARSE_AUTO,
@@ -89,13 +143,88 @@ impl Parser {
         }
     }
 
+    /// Add chunks one by one, skipping those that fail to parse. Returns a number of added chunks and errors for the rest.
+    ///
+    /// libUCL can't recover once a chunk fails to parse, so each chunk is first checked by a separate parser with the same flags, variables, variable handler and macros. Macros are called twice for every chunk that passes the check. File variables (`$FILENAME` and `$CURDIR`) of the check parser point to the current directory.
+    pub fn add_chunks_best_effort<C: AsRef<str>>(
+        &mut self,
+        chunks: &[C],
+        priority: Priority,
+        strategy: DuplicateStrategy,
+    ) -> (usize, Vec<error::UclError>) {
+        let mut added = 0;
+        let mut errors = Vec::new();
+        for chunk in chunks {
+            let result = self
+                .check_parser()
+                .add_chunk_full(chunk, priority, strategy)
+                .and_then(|_| self.add_chunk_full(chunk, priority, strategy));
+            match result {
+                Ok(()) => added += 1,
+                Err(err) => errors.push(err),
+            }
+        }
+        (added, errors)
+    }
+
+    /// A parser with the same flags, variables and handlers as this one. Handlers stay owned by this parser, so it must be dropped first.
+    fn check_parser(&self) -> Parser {
+        let parser = Parser::with_flags(self.flags);
+        unsafe {
+            for (var, value) in self.variables.iter() {
+                let var = utils::to_c_string(var);
+                let value = utils::to_c_string(value);
+                ucl_parser_register_variable(parser.parser, var.as_ptr(), value.as_ptr());
+            }
+            for (name, handler, ud) in self.macros.iter() {
+                ucl_parser_register_macro(parser.parser, name.as_ptr(), *handler, *ud);
+            }
+            let (handler, ud) = self.raw_var_handler;
+            ucl_parser_set_variables_handler(parser.parser, handler, ud);
+        }
+        parser
+    }
+
+    /// Add keys of `object` to the parser, e.g. to seed a new parser with what another one has parsed.
+    ///
+    /// libUCL can't add an object to a parser directly, so top-level keys are grouped by their priority, emitted in UCL format and added as one chunk for every priority. Values keep their types as far as UCL format can express them: time values become floats (seconds). Priorities of nested values are not kept.
+    pub fn add_object(&mut self, object: &ObjectRef) -> Result<(), error::UclError> {
+        let mut chunks: BTreeMap<c_uint, Object> = BTreeMap::new();
+        for value in object.iterate_raw(true) {
+            let key = match value.key() {
+                Some(key) => key,
+                None => continue,
+            };
+            chunks
+                .entry(value.priority().as_c_uint())
+                .or_insert_with(Object::new_object)
+                .insert(key, value.deep_copy());
+        }
+        for (priority, chunk) in chunks {
+            let chunk = chunk.emit_config().ok_or_else(|| {
+                error::UclError::new(error::UclErrorType::Other, "failed to emit an object")
+            })?;
+            self.add_chunk_full(
+                chunk,
+                Priority::from(priority),
+                DuplicateStrategy::UCL_DUPLICATE_APPEND,
+            )?;
+        }
+        Ok(())
+    }
+
     /// Add a file by a file path to the parser. This function uses mmap call to load file, therefore, it should not be shrunk during parsing.
+    ///
+    /// Parser created with `ParserFlags::NO_FILEVARS` flag uses `add_file_copy` instead.
     pub fn add_file_full<F: AsRef<Path>>(
         &mut self,
         file: F,
         priority: Priority,
         strategy: DuplicateStrategy,
     ) -> Result<(), error::UclError> {
+        if self.filevars_disabled() {
+            return self.add_file_copy(file, priority, strategy);
+        }
         let file_path = utils::to_c_string(file.as_ref().to_string_lossy());
         let result = unsafe {
             ucl_parser_add_file_full(
@@ -114,6 +243,43 @@ impl Parser {
         }
     }
 
+    /// Add a file by a file path to the parser. Unlike `add_file_full` this function reads the whole file into memory first, so it's safe to use on files that can be rewritten during parsing.
+    ///
+    /// File variables (`$FILENAME` and `$CURDIR`) are set to this file, same as `add_file_full` does.
+    pub fn add_file_copy<F: AsRef<Path>>(
+        &mut self,
+        file: F,
+        priority: Priority,
+        strategy: DuplicateStrategy,
+    ) -> Result<(), error::UclError> {
+        let file = file.as_ref();
+        let chunk = std::fs::read_to_string(file).map_err(|e| {
+            error::UclError::new(
+                error::UclErrorType::Io,
+                format!("cannot read file {}: {}", file.display(), e),
+            )
+        })?;
+        self.set_filevars(file, false)?;
+        self.add_chunk_full(chunk, priority, strategy)
+    }
+
+    /// Read everything from `reader` and add it as a chunk to the parser. Read errors are returned as `UclErrorType::Io`.
+    pub fn add_reader<R: std::io::Read>(
+        &mut self,
+        mut reader: R,
+        priority: Priority,
+        strategy: DuplicateStrategy,
+    ) -> Result<(), error::UclError> {
+        let mut chunk = String::new();
+        reader.read_to_string(&mut chunk).map_err(|e| {
+            error::UclError::new(
+                error::UclErrorType::Io,
+                format!("cannot read source: {}", e),
+            )
+        })?;
+        self.add_chunk_full(chunk, priority, strategy)
+    }
+
     #[cfg(unix)]
     pub fn add_fd_full<F: AsRawFd>(
         &mut self,
@@ -153,11 +319,16 @@ impl Parser {
     ///
     /// - `$FILENAME` - `/etc/something.conf`
     /// - `$CURDIR` - `/etc`
+    ///
+    /// Does nothing if parser was created with `ParserFlags::NO_FILEVARS` flag.
     pub fn set_filevars<F: AsRef<Path>>(
         &mut self,
         filename: F,
         need_expand: bool,
     ) -> Result<(), error::UclError> {
+        if self.filevars_disabled() {
+            return Ok(());
+        }
         let file_path = utils::to_c_string(filename.as_ref().to_string_lossy());
         let result =
             unsafe { ucl_parser_set_filevars(self.parser, file_path.as_ptr(), need_expand) };
@@ -169,7 +340,7 @@ impl Parser {
     }
 
     /// Get a top object for a parser.
-    pub fn get_object(&mut self) -> Result<Object, error::UclError> {
+    pub fn get_object(&self) -> Result<Object, error::UclError> {
         let result = unsafe { ucl_parser_get_object(self.parser) };
         if !result.is_null() {
             Ok(Object::from_c_ptr(result).expect("Failed to build object from non-null pointer"))
@@ -178,6 +349,23 @@ impl Parser {
         }
     }
 
+    /// Get a top object for a parser together with its top-level keys that are not in `known_keys`.
+    ///
+    /// Non-fatal way to find unknown keys: it's up to the caller to decide what to do with them.
+    pub fn get_object_checked(
+        &mut self,
+        known_keys: &[&str],
+    ) -> Result<(Object, Vec<String>), error::UclError> {
+        let root = self.get_object()?;
+        let extras = root
+            .iterate_raw(true)
+            .into_iter()
+            .filter_map(|obj| obj.key())
+            .filter(|key| !known_keys.contains(&key.as_str()))
+            .collect();
+        Ok((root, extras))
+    }
+
     /// Register new variable `$var` that should be replaced by the parser to the `value` string.
     /// Variables need to be registered _before_ they are referenced.
     ///
@@ -188,6 +376,8 @@ impl Parser {
         var: K,
         value: V,
     ) -> &mut Self {
+        self.variables
+            .insert(var.as_ref().to_string(), value.as_ref().to_string());
         let var = utils::to_c_string(var);
         let value = utils::to_c_string(value);
         unsafe {
@@ -196,6 +386,42 @@ impl Parser {
         self
     }
 
+    /// Register every `(var, value)` pair as a variable, same as calling `register_variable` for each of them.
+    ///
+    /// #### Panics
+    /// This function panics if either `var` or `value` has `\0`.
+    pub fn register_variables<I, K, V>(&mut self, vars: I) -> &mut Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        for (var, value) in vars {
+            self.register_variable(var, value);
+        }
+        self
+    }
+
+    /// Register new variable `$var` with an integer value. Value is formatted the same way it would be written in UCL.
+    ///
+    /// Like any other variable it's expanded as a string, `port = $port` is a string.
+    ///
+    /// #### Panics
+    /// This function panics if `var` has `\0`.
+    pub fn register_variable_int<K: AsRef<str>>(&mut self, var: K, value: i64) -> &mut Self {
+        self.register_variable(var, value.to_string())
+    }
+
+    /// Register new variable `$var` with a boolean value, either `true` or `false`.
+    ///
+    /// Like any other variable it's expanded as a string.
+    ///
+    /// #### Panics
+    /// This function panics if `var` has `\0`.
+    pub fn register_variable_bool<K: AsRef<str>>(&mut self, var: K, value: bool) -> &mut Self {
+        self.register_variable(var, value.to_string())
+    }
+
     /// Register function as an unknown variable handler. Parser can only have one handler.
     ///
     /// - *handler* - a function pointer
@@ -203,21 +429,24 @@ impl Parser {
     ///
     /// # Safety
     ///
-    /// Both object behind `ud` and function behind `handler` need to live at least as long as the parser.
+    /// Both object behind `ud` and function behind `handler` need to live at least as long as the parser. If the parser is later moved to another thread with [`Parser::into_send`](#method.into_send), they need to be safe to use from that thread too.
     pub unsafe fn set_variables_handler_raw(
         &mut self,
         handler: ucl_variable_handler,
         ud: *mut std::ffi::c_void,
     ) -> &mut Self {
         ucl_parser_set_variables_handler(self.parser, handler, ud);
+        self.raw_var_handler = (handler, ud);
         self
     }
 
-    /// A safe counterpart of [`Parser::set_variable_handler_raw`](#method.set_variables_handler_raw). Unlike unsafe version this one takes ownership of a handler and ensures it stays alive as long as parser does.
+    /// A safe counterpart of [`Parser::set_variables_handler_raw`](#method.set_variables_handler_raw). Unlike unsafe version this one takes ownership of a handler and ensures it stays alive as long as parser does.
     ///
     /// ### Caveats
     ///
-    /// Parser can have only bar handler. In order to have multiple, please use [`CompoundHandler`](../../variable_handlers/compound/struct.CompoundHandler.html) to join multiple handlers into one.
+    /// Parser can have only one handler, setting a new one drops the previous. In order to have multiple, please use [`CompoundHandler`](../../variable_handlers/compound/struct.CompoundHandler.html) to join multiple handlers into one.
+    ///
+    /// libUCL 0.8 doesn't count values returned by a handler when it allocates an expanded string, so a value longer than the rest of that string is written past its end. See `var_handler_long_value` test.
     pub fn set_variables_handler(&mut self, handler: Box<dyn VariableHandler>) -> &mut Self {
         let mut handler = handler;
         let (state, callback) = handler.get_fn_ptr_and_data();
@@ -227,6 +456,86 @@ impl Parser {
         }
         self
     }
+
+    /// Register function as a handler for macro `.name`.
+    ///
+    /// - *handler* - a function pointer
+    /// - *ud* - an opaque pointer that will be passed to a handler
+    ///
+    /// #### Panics
+    /// This function panics if `name` has `\0`.
+    ///
+    /// # Safety
+    ///
+    /// Both object behind `ud` and function behind `handler` need to live at least as long as the parser. If the parser is later moved to another thread with [`Parser::into_send`](#method.into_send), they need to be safe to use from that thread too.
+    pub unsafe fn register_macro_raw<N: AsRef<str>>(
+        &mut self,
+        name: N,
+        handler: ucl_macro_handler,
+        ud: *mut std::ffi::c_void,
+    ) -> &mut Self {
+        let name = utils::to_c_string(name);
+        ucl_parser_register_macro(self.parser, name.as_ptr(), handler, ud);
+        self.macros.push((name, handler, ud));
+        self
+    }
+
+    /// A safe counterpart of [`Parser::register_macro_raw`](#method.register_macro_raw). Register a handler for macro `.name`. Parser takes ownership of a handler and ensures it stays alive as long as parser does.
+    ///
+    /// #### Panics
+    /// This function panics if `name` has `\0`.
+    pub fn register_macro<N: AsRef<str>>(
+        &mut self,
+        name: N,
+        handler: Box<dyn MacroHandler>,
+    ) -> &mut Self {
+        let name = utils::to_c_string(name);
+        let mut handler = handler;
+        let (state, callback) = handler.get_fn_ptr_and_data();
+        self.macro_handlers.push(handler);
+        unsafe {
+            ucl_parser_register_macro(self.parser, name.as_ptr(), callback, state);
+        }
+        self.macros.push((name, callback, state));
+        self
+    }
+
+    /// Wrap parser into [`SendParser`](struct.SendParser.html), so it can be moved to another thread.
+    ///
+    /// Handlers are not required to be `Send`, so a parser that has a variable or a macro handler registered is returned back as an error.
+    #[allow(clippy::result_large_err)]
+    pub fn into_send(self) -> Result<SendParser, Parser> {
+        if self.var_handler.is_some() || !self.macro_handlers.is_empty() {
+            Err(self)
+        } else {
+            Ok(SendParser(self))
+        }
+    }
+}
+
+/// A parser without registered handlers that can be moved between threads. Use [`SendParser::into_inner`](#method.into_inner) to get the parser back.
+#[derive(Debug)]
+pub struct SendParser(Parser);
+
+impl SendParser {
+    /// Unwrap the parser.
+    pub fn into_inner(self) -> Parser {
+        self.0
+    }
+}
+
+// SAFETY: libUCL parser has no global or thread-local state, and the pointer is owned exclusively by the wrapped `Parser`, so it's safe to use it from another thread as long as it's not used from two threads at once. The only non-`Send` state `Parser` can hold are handlers, and `into_send` refuses parsers that have any.
+unsafe impl Send for SendParser {}
+
+/// Where value of a field comes from, reported by derived builders with `#[ucl(track_presence)]`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum FieldSource {
+    /// Key is present in parsed sources.
+    Config,
+    /// Key is present and its value is the one from a file included with `env_path`.
+    Env,
+    /// Key is missing, so field got its default value.
+    Default,
 }
 
 impl Drop for Parser {
@@ -238,7 +547,7 @@ impl Drop for Parser {
 impl fmt::Debug for Parser {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Parser")
-            .field("flags", &self.flags.0)
+            .field("flags", &self.flags)
             .finish()
     }
 }
@@ -246,7 +555,7 @@ impl fmt::Debug for Parser {
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::traits::VariableHandler;
+    use crate::traits::{MacroHandler, VariableHandler};
     use crate::{UclErrorType, DEFAULT_DUPLICATE_STRATEGY};
     use bitflags::_core::ptr::slice_from_raw_parts;
 
@@ -257,7 +566,309 @@ mod test {
         let chunk = parser.add_chunk_full(input, Priority::default(), DEFAULT_DUPLICATE_STRATEGY);
         assert!(chunk.is_err());
         let err = chunk.unwrap_err();
-        assert_eq!(UclErrorType::Syntax, err.kind())
+        assert_eq!(UclErrorType::Syntax, err.kind());
+        assert_eq!(libucl_bind::ucl_error_t::UCL_ESYNTAX as i32, err.code_raw());
+    }
+
+    #[test]
+    fn combined_flags() {
+        let mut parser = Parser::with_flags(ParserFlags::KEY_LOWERCASE | ParserFlags::NO_TIME);
+        parser
+            .add_chunk_full(
+                "Server_Name = web\nTimeout = 10s",
+                Priority::default(),
+                DEFAULT_DUPLICATE_STRATEGY,
+            )
+            .unwrap();
+        let root = parser.get_object().unwrap();
+        assert_eq!(
+            "web",
+            root.lookup("server_name").unwrap().as_string().unwrap()
+        );
+        assert_eq!(
+            Some("server_name".to_string()),
+            root.lookup("server_name").unwrap().key()
+        );
+        assert_eq!("10s", root.lookup("timeout").unwrap().as_string().unwrap());
+        assert_eq!(
+            "Parser { flags: KEY_LOWERCASE | NO_TIME }",
+            format!("{:?}", parser)
+        );
+    }
+
+    #[test]
+    fn reset() {
+        let mut parser = Parser::default();
+        parser.register_variable("NAME", "first");
+        parser
+            .add_chunk_full("a = $NAME", Priority::default(), DEFAULT_DUPLICATE_STRATEGY)
+            .unwrap();
+        let first = parser.get_object().unwrap();
+
+        parser.reset();
+        parser
+            .add_chunk_full("b = $NAME", Priority::default(), DEFAULT_DUPLICATE_STRATEGY)
+            .unwrap();
+        let second = parser.get_object().unwrap();
+
+        assert_eq!(
+            Some("first".to_string()),
+            first.lookup("a").unwrap().as_string()
+        );
+        assert!(first.lookup("b").is_none());
+        assert!(second.lookup("a").is_none());
+        assert_eq!(
+            Some("$NAME".to_string()),
+            second.lookup("b").unwrap().as_string()
+        );
+    }
+
+    #[test]
+    fn chunks_best_effort() {
+        let chunks = ["good = yes\n", "broken =", "also_good = 1\n"];
+        let mut parser = Parser::default();
+        let (added, errors) =
+            parser.add_chunks_best_effort(&chunks, Priority::default(), DEFAULT_DUPLICATE_STRATEGY);
+        assert_eq!(2, added);
+        assert_eq!(1, errors.len());
+        assert_eq!(UclErrorType::Syntax, errors[0].kind());
+
+        let root = parser.get_object().unwrap();
+        assert_eq!(Some(true), root.lookup("good").unwrap().as_bool());
+        assert_eq!(Some(1), root.lookup("also_good").unwrap().as_i64());
+        assert!(root.lookup("broken").is_none());
+    }
+
+    #[test]
+    fn chunks_best_effort_with_macro() {
+        let allow = |data: *const ::std::os::raw::c_uchar,
+                     len: usize,
+                     _args: *const libucl_bind::ucl_object_t| {
+            let arg = unsafe { slice_from_raw_parts(data, len).as_ref().unwrap() };
+            arg == b"yes"
+        };
+        let chunks = [
+            ".allow \"yes\"\nhost = $host\n",
+            ".allow \"no\"\ndenied = 1\n",
+            "port = 80\n",
+        ];
+        let mut parser = Parser::default();
+        parser.register_macro("allow", Box::new(allow));
+        parser.register_variable("host", "localhost");
+        let (added, errors) =
+            parser.add_chunks_best_effort(&chunks, Priority::default(), DEFAULT_DUPLICATE_STRATEGY);
+        assert_eq!(2, added);
+        assert_eq!(1, errors.len());
+
+        let root = parser.get_object().unwrap();
+        assert_eq!(
+            Some("localhost".to_string()),
+            root.lookup("host").unwrap().as_string()
+        );
+        assert_eq!(Some(80), root.lookup("port").unwrap().as_i64());
+        assert!(root.lookup("denied").is_none());
+    }
+
+    #[test]
+    fn file_copy() {
+        let mut parser = Parser::default();
+        parser
+            .add_file_copy(
+                concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/key_one.ucl"),
+                Priority::default(),
+                DEFAULT_DUPLICATE_STRATEGY,
+            )
+            .unwrap();
+        let root = parser.get_object().unwrap();
+        assert_eq!(
+            Some("asd".to_string()),
+            root.lookup("key_one").unwrap().as_string()
+        );
+
+        let err = parser
+            .add_file_copy(
+                "/nonexistent/file.ucl",
+                Priority::default(),
+                DEFAULT_DUPLICATE_STRATEGY,
+            )
+            .unwrap_err();
+        assert_eq!(UclErrorType::Io, err.kind());
+    }
+
+    #[test]
+    fn reader() {
+        let mut parser = Parser::default();
+        let reader = std::io::Cursor::new(b"key = value\nport = 80".to_vec());
+        parser
+            .add_reader(reader, Priority::default(), DEFAULT_DUPLICATE_STRATEGY)
+            .unwrap();
+        let root = parser.get_object().unwrap();
+        assert_eq!(Some(80), root.lookup("port").unwrap().as_i64());
+
+        let reader = std::io::Cursor::new(vec![0xff, 0xfe]);
+        let err = parser
+            .add_reader(reader, Priority::default(), DEFAULT_DUPLICATE_STRATEGY)
+            .unwrap_err();
+        assert_eq!(UclErrorType::Io, err.kind());
+    }
+
+    #[test]
+    fn send_to_thread() {
+        let parser = Parser::default().into_send().unwrap();
+        let handle = std::thread::spawn(move || {
+            let mut parser = parser.into_inner();
+            parser
+                .add_chunk_full(
+                    "key = value",
+                    Priority::default(),
+                    DEFAULT_DUPLICATE_STRATEGY,
+                )
+                .unwrap();
+            let root = parser.get_object().unwrap();
+            root.lookup("key").unwrap().as_string()
+        });
+        assert_eq!(Some("value".to_string()), handle.join().unwrap());
+
+        let mut parser = Parser::default();
+        parser.set_variables_handler(Box::new(
+            crate::variable_handlers::env::EnvVariableHandler::default(),
+        ));
+        assert!(parser.into_send().is_err());
+    }
+
+    #[test]
+    fn chunk_dropped_after_add() {
+        let zerocopy = libucl_bind::ucl_parser_flags::UCL_PARSER_ZEROCOPY.0;
+        let flags =
+            unsafe { ParserFlags::from_bits_unchecked(DEFAULT_PARSER_FLAG.bits() | zerocopy) };
+        let mut parser = Parser::with_flags(flags);
+        // Chunk is moved into the call and dropped when it returns.
+        parser
+            .add_chunk_full(
+                String::from("key = value\nlist = [one, two]"),
+                Priority::default(),
+                DEFAULT_DUPLICATE_STRATEGY,
+            )
+            .unwrap();
+        let root = parser.get_object().unwrap();
+        drop(parser);
+        assert_eq!(
+            Some("value".to_string()),
+            root.lookup("key").unwrap().as_string()
+        );
+        let list: Vec<String> = root
+            .lookup("list")
+            .unwrap()
+            .iter()
+            .filter_map(|obj| obj.as_string())
+            .collect();
+        assert_eq!(vec!["one", "two"], list);
+    }
+
+    #[test]
+    fn register_variables_from_map() {
+        let mut vars = std::collections::HashMap::new();
+        vars.insert("host", "localhost");
+        vars.insert("port", "8080");
+        let mut parser = Parser::default();
+        parser.register_variables(vars);
+        parser
+            .add_chunk_full(
+                "url = \"http://$host:$port\"",
+                Priority::default(),
+                DEFAULT_DUPLICATE_STRATEGY,
+            )
+            .unwrap();
+        let root = parser.get_object().unwrap();
+        assert_eq!(
+            Some("http://localhost:8080".to_string()),
+            root.lookup("url").unwrap().as_string()
+        );
+    }
+
+    #[test]
+    fn register_variable_int_and_bool() {
+        let mut parser = Parser::default();
+        parser
+            .register_variable_int("port", 8080)
+            .register_variable_bool("debug", true);
+        parser
+            .add_chunk_full(
+                "url = \"http://localhost:$port\"\nport = $port\ndebug = $debug",
+                Priority::default(),
+                DEFAULT_DUPLICATE_STRATEGY,
+            )
+            .unwrap();
+        let root = parser.get_object().unwrap();
+        assert_eq!(
+            Some("http://localhost:8080".to_string()),
+            root.lookup("url").unwrap().as_string()
+        );
+        assert_eq!(
+            Some("8080".to_string()),
+            root.lookup("port").unwrap().as_string()
+        );
+        assert_eq!(
+            Some("true".to_string()),
+            root.lookup("debug").unwrap().as_string()
+        );
+    }
+
+    #[test]
+    fn no_filevars() {
+        let input = "file = \"$FILENAME\"\ndir = \"$CURDIR\"";
+        let mut parser = Parser::without_filevars();
+        parser.set_filevars("/etc/app/app.conf", false).unwrap();
+        parser
+            .add_chunk_full(input, Priority::default(), DEFAULT_DUPLICATE_STRATEGY)
+            .unwrap();
+        let root = parser.get_object().unwrap();
+        assert_eq!(
+            Some("$FILENAME".to_string()),
+            root.lookup("file").unwrap().as_string()
+        );
+        assert_eq!(
+            Some("$CURDIR".to_string()),
+            root.lookup("dir").unwrap().as_string()
+        );
+
+        let mut parser = Parser::default();
+        parser.set_filevars("/etc/app/app.conf", false).unwrap();
+        parser
+            .add_chunk_full(input, Priority::default(), DEFAULT_DUPLICATE_STRATEGY)
+            .unwrap();
+        let root = parser.get_object().unwrap();
+        assert_eq!(
+            Some("/etc/app".to_string()),
+            root.lookup("dir").unwrap().as_string()
+        );
+    }
+
+    #[test]
+    fn error_position() {
+        let mut parser = Parser::default();
+        let err = parser
+            .add_chunk_full(
+                "a = 1;\nb {\n  c = ]\n}\n",
+                Priority::default(),
+                DEFAULT_DUPLICATE_STRATEGY,
+            )
+            .unwrap_err();
+        assert_eq!(UclErrorType::Syntax, err.kind());
+        assert_eq!(Some(3), err.line());
+        assert!(err.column().is_some());
+    }
+
+    #[test]
+    fn object_checked() {
+        let input = "name = web\nport = 80\nprot = 8080\ntag = a\ntag = b\nextra { x = 1 }";
+        let mut parser = Parser::default();
+        parser
+            .add_chunk_full(input, Priority::default(), DEFAULT_DUPLICATE_STRATEGY)
+            .unwrap();
+        let (root, extras) = parser.get_object_checked(&["name", "port", "tag"]).unwrap();
+        assert_eq!(vec!["prot".to_string(), "extra".to_string()], extras);
+        assert_eq!(Some(80), root.lookup("port").unwrap().as_i64());
     }
 
     #[test]
@@ -279,7 +890,7 @@ mod test {
             }
             if var.eq("WWW") {
                 let test = "asd";
-                let size = test.as_bytes().len();
+                let size = test.len();
                 unsafe {
                     *replace = libc::malloc(size).cast();
                     *replace_len = size;
@@ -314,6 +925,71 @@ mod test {
         assert_eq!("asd", object.as_str());
     }
 
+    #[test]
+    fn macro_handler_uppercase() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let seen: Rc<RefCell<Vec<String>>> = Default::default();
+        let handler_seen = seen.clone();
+        let upper = move |data: *const ::std::os::raw::c_uchar,
+                          len: usize,
+                          _args: *const libucl_bind::ucl_object_t| {
+            let arg = unsafe {
+                let slice = slice_from_raw_parts(data, len).as_ref().unwrap();
+                std::str::from_utf8(slice).unwrap()
+            };
+            handler_seen.borrow_mut().push(arg.to_uppercase());
+            true
+        };
+
+        let mut parser = Parser::default();
+        parser.register_macro("upper", Box::new(upper));
+        parser
+            .add_chunk_full(
+                ".upper \"hello\"\n.upper \"world\"\nkey = value",
+                Priority::default(),
+                DEFAULT_DUPLICATE_STRATEGY,
+            )
+            .unwrap();
+        let root = parser.get_object().unwrap();
+        assert_eq!(vec!["HELLO", "WORLD"], *seen.borrow());
+        assert_eq!(
+            Some("value".to_string()),
+            root.lookup("key").unwrap().as_string()
+        );
+    }
+
+    #[test]
+    fn macro_handler_raw() {
+        let mut seen = Vec::new();
+        let mut upper = |data: *const ::std::os::raw::c_uchar,
+                         len: usize,
+                         _args: *const libucl_bind::ucl_object_t| {
+            let arg = unsafe {
+                let slice = slice_from_raw_parts(data, len).as_ref().unwrap();
+                std::str::from_utf8(slice).unwrap()
+            };
+            seen.push(arg.to_uppercase());
+            true
+        };
+        let (state, callback) = upper.get_fn_ptr_and_data();
+
+        let mut parser = Parser::default();
+        unsafe {
+            parser.register_macro_raw("upper", callback, state);
+        }
+        parser
+            .add_chunk_full(
+                ".upper \"hello\"",
+                Priority::default(),
+                DEFAULT_DUPLICATE_STRATEGY,
+            )
+            .unwrap();
+        drop(parser);
+        assert_eq!(vec!["HELLO"], seen);
+    }
+
     #[test]
     fn var_handler_with_closure() {
         let mut basic = |data: *const ::std::os::raw::c_uchar,
@@ -330,7 +1006,7 @@ mod test {
             }
             if var.eq("WWW") {
                 let test = "asd";
-                let size = test.as_bytes().len();
+                let size = test.len();
                 unsafe {
                     *replace = libc::malloc(size).cast();
                     *replace_len = size;
@@ -383,7 +1059,7 @@ mod test {
             }
             if var.eq("WWW") {
                 let test = "asd";
-                let size = test.as_bytes().len();
+                let size = test.len();
                 unsafe {
                     *replace = libc::malloc(size).cast();
                     *replace_len = size;
@@ -414,4 +1090,71 @@ mod test {
         let object = looked_up_object.as_string().unwrap();
         assert_eq!("asd", object.as_str());
     }
+
+    #[test]
+    fn var_handler_replaced() {
+        // Handler state lives only inside of the parser.
+        fn handler(value: &str) -> Box<dyn VariableHandler> {
+            let value = value.to_string();
+            Box::new(
+                move |data: *const ::std::os::raw::c_uchar,
+                      len: usize,
+                      replace: *mut *mut ::std::os::raw::c_uchar,
+                      replace_len: *mut usize,
+                      need_free: *mut bool| {
+                    let var = unsafe { slice_from_raw_parts(data, len).as_ref().unwrap() };
+                    if var != b"WWW" {
+                        return false;
+                    }
+                    unsafe {
+                        *replace = libc::malloc(value.len()).cast();
+                        *replace_len = value.len();
+                        value.as_ptr().copy_to_nonoverlapping(*replace, value.len());
+                        *need_free = true;
+                    }
+                    true
+                },
+            )
+        }
+
+        let mut parser = Parser::default();
+        parser.set_variables_handler(handler("asd"));
+        parser.set_variables_handler(handler("dsa"));
+        parser
+            .add_chunk_full(
+                "key = \"${WWW}\"",
+                Priority::default(),
+                DEFAULT_DUPLICATE_STRATEGY,
+            )
+            .unwrap();
+
+        let root = parser.get_object().unwrap();
+        assert_eq!(
+            Some("dsa".to_string()),
+            root.lookup("key").unwrap().as_string()
+        );
+    }
+
+    #[test]
+    #[ignore = "libUCL 0.8 writes handler values past the end of expanded string"]
+    fn var_handler_long_value() {
+        let long = "x".repeat(1000);
+        let mut vars = std::collections::HashMap::new();
+        vars.insert("WWW".to_string(), long.clone());
+
+        let mut parser = Parser::default();
+        parser.set_variables_handler(Box::new(
+            crate::variable_handlers::map::MapVariableHandler::new(vars),
+        ));
+        parser
+            .add_chunk_full(
+                "key = \"${WWW}\"",
+                Priority::default(),
+                DEFAULT_DUPLICATE_STRATEGY,
+            )
+            .unwrap();
+
+        let root = parser.get_object().unwrap();
+        assert_eq!(Some(long), root.lookup("key").unwrap().as_string());
+    }
 }
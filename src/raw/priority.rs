@@ -7,7 +7,7 @@ use std::os::raw::c_uint;
 ///
 /// By default, the priority of top-level object is set to zero (the lowest priority). Currently, you can define up to 16 priorities (from 0 to 16).
 /// Includes with bigger priorities will rewrite keys from the objects with lower priorities as specified by the policy.
-#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Copy, Clone, Default)]
 pub struct Priority(c_uint);
 
 impl Priority {
@@ -19,14 +19,7 @@ impl Priority {
 
     #[inline]
     fn normalize_signed(source: i64) -> Priority {
-        let priority = if source > 16 {
-            16
-        } else if source < 0 {
-            0
-        } else {
-            source
-        };
-        Priority(priority as u32)
+        Priority(source.clamp(0, 16) as u32)
     }
 
     /// Create a Priority. Values outside of 0..16 range will be changed to nearest "legal" number.
@@ -39,11 +32,11 @@ impl Priority {
     pub fn as_c_uint(self) -> c_uint {
         self.0
     }
-}
 
-impl Default for Priority {
-    fn default() -> Self {
-        Priority(0)
+    /// Get priority as a number.
+    #[inline]
+    pub fn value(self) -> u32 {
+        self.0
     }
 }
 
@@ -191,4 +184,17 @@ mod test {
         let from_i64: Priority = (-69420i64).into();
         assert_eq!(expected, from_i64);
     }
+
+    #[test]
+    fn test_ordering() {
+        assert!(Priority::new(3) < Priority::new(10));
+        assert_eq!(10, Priority::new(10).value());
+
+        let mut priorities = vec![Priority::new(15), Priority::new(0), Priority::new(7)];
+        priorities.sort();
+        assert_eq!(
+            vec![Priority::new(0), Priority::new(7), Priority::new(15)],
+            priorities
+        );
+    }
 }
@@ -0,0 +1,106 @@
+//! `serde::Serialize` for objects.
+//!
+//! Follows the same rules as conversion into `serde_json::Value`: time objects become plain numbers of seconds and keys with more than one value (implicit arrays) become sequences.
+use crate::raw::object::{Object, ObjectRef};
+use libucl_bind::ucl_type_t;
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use std::borrow::Borrow;
+
+/// Values of a single key: a scalar if there is only one, a sequence otherwise.
+struct Values(Vec<ObjectRef>);
+
+impl Serialize for Values {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.0.len() == 1 {
+            self.0[0].serialize(serializer)
+        } else {
+            serializer.collect_seq(self.0.iter())
+        }
+    }
+}
+
+impl Serialize for ObjectRef {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.kind() {
+            ucl_type_t::UCL_OBJECT => {
+                let children = self.iterate_raw(true);
+                let mut map = serializer.serialize_map(Some(children.len()))?;
+                for child in children {
+                    let key = child.key().unwrap_or_default();
+                    map.serialize_entry(&key, &Values(child.iterate_raw(false)))?;
+                }
+                map.end()
+            }
+            ucl_type_t::UCL_ARRAY => serializer.collect_seq(self.iterate_raw(true).iter()),
+            ucl_type_t::UCL_INT => match self.as_i64() {
+                Some(v) => serializer.serialize_i64(v),
+                None => serializer.serialize_unit(),
+            },
+            ucl_type_t::UCL_FLOAT | ucl_type_t::UCL_TIME => match self.as_f64() {
+                Some(v) => serializer.serialize_f64(v),
+                None => serializer.serialize_unit(),
+            },
+            ucl_type_t::UCL_STRING => match self.as_string() {
+                Some(v) => serializer.serialize_str(&v),
+                None => serializer.serialize_unit(),
+            },
+            ucl_type_t::UCL_BOOLEAN => match self.as_bool() {
+                Some(v) => serializer.serialize_bool(v),
+                None => serializer.serialize_unit(),
+            },
+            ucl_type_t::UCL_USERDATA | ucl_type_t::UCL_NULL => serializer.serialize_unit(),
+        }
+    }
+}
+
+impl Serialize for Object {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let obj: &ObjectRef = self.borrow();
+        obj.serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Parser, Priority, DEFAULT_DUPLICATE_STRATEGY};
+    use serde_json::json;
+
+    #[test]
+    fn serialize_to_json_value() {
+        let input = r#"
+        name = "web"
+        port = 8080
+        ratio = 0.5
+        enabled = yes
+        upstream {
+            hosts = ["a", "b"]
+            timeout = 10s
+        }
+        tag = one
+        tag = two
+        "#;
+        let mut parser = Parser::default();
+        parser
+            .add_chunk_full(input, Priority::default(), DEFAULT_DUPLICATE_STRATEGY)
+            .unwrap();
+        let root = parser.get_object().unwrap();
+
+        let expected = json!({
+            "name": "web",
+            "port": 8080,
+            "ratio": 0.5,
+            "enabled": true,
+            "upstream": {
+                "hosts": ["a", "b"],
+                "timeout": 10.0
+            },
+            "tag": ["one", "two"]
+        });
+        assert_eq!(expected, serde_json::to_value(&root).unwrap());
+        let upstream = root.lookup("upstream").unwrap();
+        assert_eq!(
+            expected["upstream"],
+            serde_json::to_value(&upstream).unwrap()
+        );
+    }
+}
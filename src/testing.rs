@@ -0,0 +1,99 @@
+//! Helpers for tests of configuration files.
+//!
+//! Available with `testing` feature. Functions here panic with parser or conversion error instead of returning it.
+//!
+//! ```rust
+//! use uclicious::testing;
+//!
+//! let root = testing::parse("port = 8080");
+//! assert_eq!(Some(8080), root.lookup("port").unwrap().as_i64());
+//!
+//! let ports: Vec<u16> = testing::parse_into("[80, 443]");
+//! assert_eq!(vec![80, 443], ports);
+//! ```
+use crate::raw::{Object, ObjectRef, Parser, DEFAULT_DUPLICATE_STRATEGY};
+use crate::traits::FromObject;
+use crate::Priority;
+
+/// Parse input with default parser.
+///
+/// # Panics
+///
+/// If input fails to parse.
+pub fn parse(input: &str) -> Object {
+    let mut parser = Parser::default();
+    if let Err(err) = parser.add_chunk_full(input, Priority::default(), DEFAULT_DUPLICATE_STRATEGY)
+    {
+        panic!("failed to parse input: {}\n---\n{}\n---", err, input);
+    }
+    parser
+        .get_object()
+        .unwrap_or_else(|err| panic!("failed to get parsed object: {}", err))
+}
+
+/// Parse input with default parser and convert the result into `T`.
+///
+/// # Panics
+///
+/// If input fails to parse or can't be converted.
+pub fn parse_into<T: FromObject<ObjectRef>>(input: &str) -> T {
+    let root = parse(input);
+    let (_, obj) = root.entry();
+    T::try_from(obj).unwrap_or_else(|err| {
+        panic!(
+            "failed to convert parsed object: {}\n---\n{}\n---",
+            err, input
+        )
+    })
+}
+
+/// Assert that input parses and optionally converts into a type.
+///
+/// ```rust
+/// use uclicious::assert_parses;
+///
+/// assert_parses!("enabled = yes");
+/// assert_parses!("[1, 2, 3]", Vec<i64>);
+/// ```
+#[macro_export]
+macro_rules! assert_parses {
+    ($input:expr) => {
+        $crate::testing::parse($input)
+    };
+    ($input:expr, $ty:ty) => {
+        $crate::testing::parse_into::<$ty>($input)
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn parse_object() {
+        let root = parse("name = web\nport = 8080");
+        assert_eq!(
+            Some("web".to_string()),
+            root.lookup("name").unwrap().as_string()
+        );
+
+        let map: HashMap<String, i64> = parse_into("a = 1\nb = 2");
+        assert_eq!(Some(&2), map.get("b"));
+
+        let list = assert_parses!("[1, 2]", Vec<i64>);
+        assert_eq!(vec![1, 2], list);
+    }
+
+    #[test]
+    #[should_panic(expected = "failed to parse input")]
+    fn parse_failure() {
+        parse("key = {");
+    }
+
+    #[test]
+    #[should_panic(expected = "failed to convert parsed object")]
+    fn conversion_failure() {
+        let _: Vec<i64> = parse_into("[one, two]");
+    }
+}
@@ -1,6 +1,6 @@
 //! Various traits that help you reduce boilerplate while using uclicious.
 use crate::ObjectError;
-use libucl_bind::ucl_variable_handler;
+use libucl_bind::{ucl_macro_handler, ucl_object_t, ucl_variable_handler};
 use std::os::raw::{c_uchar, c_void};
 
 /// Implement this trait on your types in order for automatic derive to work. This is a copy of `TryFrom`.
@@ -80,3 +80,53 @@ where
         unsafe { unpack_closure(self) }
     }
 }
+
+/// A safe-ish interface that can be used as macro handler. Modeled after `ucl_macro_handler`: it receives the content of the macro and its arguments.
+///
+/// Returning `false` fails the parsing.
+pub trait MacroHandler {
+    fn handle(
+        &mut self,
+        data: *const ::std::os::raw::c_uchar,
+        len: usize,
+        arguments: *const ucl_object_t,
+    ) -> bool;
+    fn get_fn_ptr_and_data(&mut self) -> (*mut c_void, ucl_macro_handler);
+}
+
+/// Unpack closure into a data (context) and function pointer, same as `unpack_closure`, but for macro handlers.
+///
+/// # Safety
+///
+/// Caller need to ensure that closure lives as log as the pointer does.
+pub(crate) unsafe fn unpack_macro_closure<F>(closure: &mut F) -> (*mut c_void, ucl_macro_handler)
+where
+    F: FnMut(*const c_uchar, usize, *const ucl_object_t) -> bool,
+{
+    extern "C" fn trampoline<F>(
+        data: *const ::std::os::raw::c_uchar,
+        len: usize,
+        arguments: *const ucl_object_t,
+        ud: *mut c_void,
+    ) -> bool
+    where
+        F: FnMut(*const c_uchar, usize, *const ucl_object_t) -> bool,
+    {
+        let closure: &mut F = unsafe { &mut *(ud as *mut F) };
+        (*closure)(data, len, arguments)
+    }
+    (closure as *mut F as *mut c_void, Some(trampoline::<F>))
+}
+
+impl<F> MacroHandler for F
+where
+    F: FnMut(*const c_uchar, usize, *const ucl_object_t) -> bool,
+{
+    fn handle(&mut self, data: *const u8, len: usize, arguments: *const ucl_object_t) -> bool {
+        self(data, len, arguments)
+    }
+
+    fn get_fn_ptr_and_data(&mut self) -> (*mut c_void, ucl_macro_handler) {
+        unsafe { unpack_macro_closure(self) }
+    }
+}
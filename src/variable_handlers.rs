@@ -3,3 +3,7 @@
 pub mod compound;
 #[cfg(feature = "vh_basic")]
 pub mod env;
+#[cfg(feature = "vh_file")]
+pub mod file;
+#[cfg(feature = "vh_map")]
+pub mod map;
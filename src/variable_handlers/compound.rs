@@ -29,7 +29,6 @@ impl Default for CompoundHandler {
                 if handler.handle(data, len, replace, replace_len, need_free) {
                     found = true;
                     break;
-                } else {
                 }
             }
             found
@@ -51,6 +50,30 @@ impl CompoundHandler {
         }
         self
     }
+
+    /// Remove a handler at `index` and return it.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    pub fn remove_handler(&mut self, index: usize) -> Box<dyn VariableHandler> {
+        self.handlers.borrow_mut().remove(index)
+    }
+
+    /// Remove all handlers. Variables are left as is until new handlers are registered.
+    pub fn clear(&mut self) {
+        self.handlers.borrow_mut().clear();
+    }
+
+    /// Number of registered handlers.
+    pub fn len(&self) -> usize {
+        self.handlers.borrow().len()
+    }
+
+    /// Returns `true` if no handlers are registered.
+    pub fn is_empty(&self) -> bool {
+        self.handlers.borrow().is_empty()
+    }
 }
 
 impl VariableHandler for CompoundHandler {
@@ -89,7 +112,7 @@ mod test {
             };
             if var.eq("WWW") {
                 let test = "asd";
-                let size = test.as_bytes().len();
+                let size = test.len();
                 unsafe {
                     *replace = libc::malloc(size).cast();
                     *replace_len = size;
@@ -115,7 +138,7 @@ mod test {
             };
             if var.eq("ZZZ") {
                 let test = "dsa";
-                let size = test.as_bytes().len();
+                let size = test.len();
                 unsafe {
                     *replace = libc::malloc(size).cast();
                     *replace_len = size;
@@ -159,4 +182,62 @@ mod test {
         let object2 = looked_up_object2.as_string().unwrap();
         assert_eq!("dsa", object2.as_str());
     }
+
+    fn replace_var(
+        name: &'static str,
+        value: &'static str,
+    ) -> impl FnMut(*const c_uchar, usize, *mut *mut c_uchar, *mut usize, *mut bool) -> bool {
+        move |data, len, replace, replace_len, need_free| {
+            let var = unsafe {
+                let slice = slice_from_raw_parts(data, len).as_ref().unwrap();
+                std::str::from_utf8(slice).unwrap()
+            };
+            if var != name {
+                return false;
+            }
+            unsafe {
+                *replace = libc::malloc(value.len()).cast();
+                *replace_len = value.len();
+                value.as_ptr().copy_to_nonoverlapping(*replace, value.len());
+                *need_free = true;
+            }
+            true
+        }
+    }
+
+    #[test]
+    fn remove_and_clear() {
+        let mut compound_handler = CompoundHandler::default();
+        compound_handler.register_handler(Box::new(replace_var("WWW", "asd")));
+        compound_handler.register_handler(Box::new(replace_var("ZZZ", "dsa")));
+        assert_eq!(2, compound_handler.len());
+        let (state, callback) = compound_handler.get_fn_ptr_and_data();
+
+        let input = r#"
+        key2 = "${ZZZ}"
+        key1 = "${WWW}"
+        "#;
+        let parse = || {
+            let mut parser = Parser::default();
+            unsafe {
+                parser.set_variables_handler_raw(callback, state);
+            }
+            parser
+                .add_chunk_full(input, Priority::default(), DEFAULT_DUPLICATE_STRATEGY)
+                .unwrap();
+            let root = parser.get_object().unwrap();
+            (
+                root.lookup("key1").unwrap().as_string().unwrap(),
+                root.lookup("key2").unwrap().as_string().unwrap(),
+            )
+        };
+
+        compound_handler.remove_handler(0);
+        assert_eq!(1, compound_handler.len());
+        assert_eq!(("${WWW}".to_string(), "dsa".to_string()), parse());
+
+        compound_handler.clear();
+        assert!(compound_handler.is_empty());
+        assert_eq!(("${WWW}".to_string(), "${ZZZ}".to_string()), parse());
+    }
 }
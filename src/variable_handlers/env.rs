@@ -13,8 +13,13 @@ pub struct EnvVariableHandler {
 }
 
 impl EnvVariableHandler {
-    /// Create a handler with prefix.
-    fn with_prefix(prefix: String) -> Self {
+    /// Create a handler that only replaces variables starting with `prefix`. Variable name is looked up in the environment as is, prefix included.
+    pub fn with_prefix(prefix: String) -> Self {
+        Self::with_mapping(prefix, false)
+    }
+
+    /// Create a handler that only replaces variables starting with `prefix`. If `strip_prefix` is set, prefix is removed before looking up the environment, e.g. `${ENV_PATH}` becomes `PATH`.
+    pub fn with_mapping(prefix: String, strip_prefix: bool) -> Self {
         let closure = move |data: *const ::std::os::raw::c_uchar,
                             len: usize,
                             replace: *mut *mut ::std::os::raw::c_uchar,
@@ -25,13 +30,16 @@ impl EnvVariableHandler {
                 std::str::from_utf8(slice).unwrap()
             };
 
-            if var.starts_with(&prefix) {
-                if let Ok(mut value) = std::env::var(var) {
-                    let bytes = unsafe { value.as_bytes_mut() };
+            if let Some(name) = var.strip_prefix(&prefix) {
+                let name = if strip_prefix { name } else { var };
+                if let Ok(value) = std::env::var(name) {
+                    // Value has to outlive this call, libUCL frees it when it's done.
+                    let size = value.len();
                     unsafe {
-                        *need_free = false;
-                        *replace = bytes.as_mut_ptr();
-                        *replace_len = bytes.len();
+                        *replace = libc::malloc(size).cast();
+                        *replace_len = size;
+                        value.as_ptr().copy_to_nonoverlapping(*replace, size);
+                        *need_free = true;
                     }
                     return true;
                 }
@@ -44,7 +52,7 @@ impl EnvVariableHandler {
     }
 }
 impl Default for EnvVariableHandler {
-    /// Create a handler with `ENV_` prefix.
+    /// Create a handler without prefix.
     fn default() -> Self {
         Self::with_prefix(String::from(""))
     }
@@ -70,11 +78,10 @@ impl VariableHandler for EnvVariableHandler {
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::traits::VariableHandler;
     use crate::{Parser, Priority, DEFAULT_DUPLICATE_STRATEGY};
 
-    #[cfg(target_os = "freebsd")]
     #[test]
+    #[cfg(target_os = "freebsd")]
     fn basic_env_var_handler() {
         let mut handler = EnvVariableHandler::with_prefix("ENV_".to_string());
         let (state, callback) = handler.get_fn_ptr_and_data();
@@ -110,4 +117,59 @@ mod test {
         let also_bad = root.lookup("also_bad").unwrap().as_string().unwrap();
         assert_eq!("${RZZYIBBEBD}", also_bad);
     }
+
+    #[test]
+    fn strip_prefix() {
+        std::env::set_var("UCLICIOUS_QPWOEIRUTY", "yes");
+
+        let input = r#"
+        stripped = "${ENV_UCLICIOUS_QPWOEIRUTY}"
+        bare = "${UCLICIOUS_QPWOEIRUTY}"
+        "#;
+
+        let mut parser = Parser::default();
+        parser.set_variables_handler(Box::new(EnvVariableHandler::with_mapping(
+            "ENV_".to_string(),
+            true,
+        )));
+        parser
+            .add_chunk_full(input, Priority::default(), DEFAULT_DUPLICATE_STRATEGY)
+            .unwrap();
+
+        let root = parser.get_object().unwrap();
+        let stripped = root.lookup("stripped").unwrap().as_string().unwrap();
+        assert_eq!("yes", stripped);
+        let bare = root.lookup("bare").unwrap().as_string().unwrap();
+        assert_eq!("${UCLICIOUS_QPWOEIRUTY}", bare);
+    }
+
+    #[test]
+    fn env_path() {
+        let path = std::env::var("PATH").unwrap();
+
+        // PATH is usually longer than the rest of the string, which overflows libUCL 0.8, so the handler is called directly.
+        let mut handler = EnvVariableHandler::with_mapping("ENV_".to_string(), true);
+        let var = "ENV_PATH";
+        let mut dst = std::ptr::null_mut();
+        let mut dst_len = 0;
+        let mut needs_free = false;
+        let found = handler.handle(
+            var.as_ptr(),
+            var.len(),
+            &mut dst,
+            &mut dst_len,
+            &mut needs_free,
+        );
+
+        assert!(found);
+        let value = unsafe {
+            std::ptr::slice_from_raw_parts(dst, dst_len)
+                .as_ref()
+                .unwrap()
+        };
+        assert_eq!(path.as_bytes(), value);
+        if needs_free {
+            unsafe { libc::free(dst.cast()) };
+        }
+    }
 }
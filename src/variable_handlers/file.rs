@@ -0,0 +1,137 @@
+//! A variable handler that lets you pull values from files, e.g. secrets mounted into a container.
+use crate::traits::{unpack_closure, VariableHandler};
+use libucl_bind::ucl_variable_handler;
+use std::ffi::c_void;
+use std::os::raw::c_uchar;
+use std::ptr::slice_from_raw_parts;
+
+/// A handler that replaces variables starting with a prefix with contents of the file, path is the rest of the variable name: `${FILE_/run/secrets/token}`.
+/// Trailing newlines are removed. Variables are left as is if file can't be read.
+pub struct FileVariableHandler {
+    closure:
+        Box<dyn FnMut(*const c_uchar, usize, *mut *mut c_uchar, *mut usize, *mut bool) -> bool>,
+}
+
+impl FileVariableHandler {
+    /// Create a handler for variables starting with `prefix`.
+    pub fn with_prefix(prefix: String) -> Self {
+        let closure = move |data: *const ::std::os::raw::c_uchar,
+                            len: usize,
+                            replace: *mut *mut ::std::os::raw::c_uchar,
+                            replace_len: *mut usize,
+                            need_free: *mut bool| {
+            let var = unsafe {
+                let slice = slice_from_raw_parts(data, len).as_ref().unwrap();
+                std::str::from_utf8(slice).unwrap()
+            };
+
+            if let Some(path) = var.strip_prefix(&prefix) {
+                if let Ok(contents) = std::fs::read_to_string(path) {
+                    let value = contents.trim_end_matches(&['\r', '\n'][..]);
+                    // Value has to outlive this call, libUCL frees it when it's done.
+                    let size = value.len();
+                    unsafe {
+                        *replace = libc::malloc(size).cast();
+                        *replace_len = size;
+                        value.as_ptr().copy_to_nonoverlapping(*replace, size);
+                        *need_free = true;
+                    }
+                    return true;
+                }
+            }
+            false
+        };
+        FileVariableHandler {
+            closure: Box::new(closure),
+        }
+    }
+}
+
+impl Default for FileVariableHandler {
+    /// Create a handler with `FILE_` prefix.
+    fn default() -> Self {
+        Self::with_prefix(String::from("FILE_"))
+    }
+}
+
+impl VariableHandler for FileVariableHandler {
+    fn handle(
+        &mut self,
+        ptr: *const u8,
+        len: usize,
+        dst: *mut *mut u8,
+        dst_len: *mut usize,
+        needs_free: *mut bool,
+    ) -> bool {
+        self.closure.handle(ptr, len, dst, dst_len, needs_free)
+    }
+
+    fn get_fn_ptr_and_data(&mut self) -> (*mut c_void, ucl_variable_handler) {
+        unsafe { unpack_closure(&mut self.closure) }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Parser, Priority, DEFAULT_DUPLICATE_STRATEGY};
+
+    #[test]
+    fn file_var_handler() {
+        let path = std::env::temp_dir().join("uclicious_file_var_handler");
+        std::fs::write(&path, "hunter2\n").unwrap();
+        let missing = std::env::temp_dir().join("uclicious_file_var_handler_missing");
+        let _ = std::fs::remove_file(&missing);
+
+        let input = format!(
+            "secret = \"${{FILE_{}}}\"\nmissing = \"${{FILE_{}}}\"",
+            path.display(),
+            missing.display()
+        );
+
+        let mut parser = Parser::default();
+        parser.set_variables_handler(Box::new(FileVariableHandler::default()));
+        parser
+            .add_chunk_full(input, Priority::default(), DEFAULT_DUPLICATE_STRATEGY)
+            .unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let root = parser.get_object().unwrap();
+        let secret = root.lookup("secret").unwrap().as_string().unwrap();
+        assert_eq!("hunter2", secret);
+        let unexpanded = root.lookup("missing").unwrap().as_string().unwrap();
+        assert_eq!(format!("${{FILE_{}}}", missing.display()), unexpanded);
+    }
+
+    #[test]
+    fn large_file() {
+        let path = std::env::temp_dir().join("uclicious_file_var_handler_large");
+        let cert = format!(
+            "-----BEGIN CERTIFICATE-----\n{}\n-----END CERTIFICATE-----",
+            "A".repeat(4096)
+        );
+        std::fs::write(&path, format!("{}\n", cert)).unwrap();
+
+        // Values longer than the rest of the string overflow libUCL 0.8, so the handler is called directly.
+        let var = format!("FILE_{}", path.display());
+        let mut handler = FileVariableHandler::default();
+        let mut dst = std::ptr::null_mut();
+        let mut dst_len = 0;
+        let mut needs_free = false;
+        let found = handler.handle(
+            var.as_ptr(),
+            var.len(),
+            &mut dst,
+            &mut dst_len,
+            &mut needs_free,
+        );
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(found);
+        let value = unsafe { slice_from_raw_parts(dst, dst_len).as_ref().unwrap() };
+        assert_eq!(cert.as_bytes(), value);
+        if needs_free {
+            unsafe { libc::free(dst.cast()) };
+        }
+    }
+}
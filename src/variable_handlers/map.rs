@@ -0,0 +1,95 @@
+//! A variable handler that looks up values in a map.
+use crate::traits::{unpack_closure, VariableHandler};
+use libucl_bind::ucl_variable_handler;
+use std::collections::HashMap;
+use std::ffi::c_void;
+use std::os::raw::c_uchar;
+use std::ptr::slice_from_raw_parts;
+
+/// A handler that replaces variables with values from a `HashMap`. Variables not in the map are left as is.
+pub struct MapVariableHandler {
+    closure:
+        Box<dyn FnMut(*const c_uchar, usize, *mut *mut c_uchar, *mut usize, *mut bool) -> bool>,
+}
+
+impl MapVariableHandler {
+    /// Create a handler with given variables.
+    pub fn new(vars: HashMap<String, String>) -> Self {
+        let closure = move |data: *const ::std::os::raw::c_uchar,
+                            len: usize,
+                            replace: *mut *mut ::std::os::raw::c_uchar,
+                            replace_len: *mut usize,
+                            need_free: *mut bool| {
+            let var = unsafe {
+                let slice = slice_from_raw_parts(data, len).as_ref().unwrap();
+                std::str::from_utf8(slice).unwrap()
+            };
+
+            if let Some(value) = vars.get(var) {
+                // Value has to outlive this call, libUCL frees it when it's done.
+                let size = value.len();
+                unsafe {
+                    *replace = libc::malloc(size).cast();
+                    *replace_len = size;
+                    value.as_ptr().copy_to_nonoverlapping(*replace, size);
+                    *need_free = true;
+                }
+                return true;
+            }
+            false
+        };
+        MapVariableHandler {
+            closure: Box::new(closure),
+        }
+    }
+}
+
+impl From<HashMap<String, String>> for MapVariableHandler {
+    fn from(vars: HashMap<String, String>) -> Self {
+        Self::new(vars)
+    }
+}
+
+impl VariableHandler for MapVariableHandler {
+    fn handle(
+        &mut self,
+        ptr: *const u8,
+        len: usize,
+        dst: *mut *mut u8,
+        dst_len: *mut usize,
+        needs_free: *mut bool,
+    ) -> bool {
+        self.closure.handle(ptr, len, dst, dst_len, needs_free)
+    }
+
+    fn get_fn_ptr_and_data(&mut self) -> (*mut c_void, ucl_variable_handler) {
+        unsafe { unpack_closure(&mut self.closure) }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Parser, Priority, DEFAULT_DUPLICATE_STRATEGY};
+
+    #[test]
+    fn map_var_handler() {
+        let mut vars = HashMap::new();
+        vars.insert("WWW".to_string(), "asd".to_string());
+        let input = r#"
+        key = "${WWW}"
+        other = "${ZZZ}"
+        "#;
+        let mut parser = Parser::default();
+        parser.set_variables_handler(Box::new(MapVariableHandler::new(vars)));
+        parser
+            .add_chunk_full(input, Priority::default(), DEFAULT_DUPLICATE_STRATEGY)
+            .unwrap();
+
+        let root = parser.get_object().unwrap();
+        let key = root.lookup("key").unwrap().as_string().unwrap();
+        assert_eq!("asd", key);
+        let other = root.lookup("other").unwrap().as_string().unwrap();
+        assert_eq!("${ZZZ}", other);
+    }
+}
@@ -1,7 +1,12 @@
+use std::collections::{BTreeMap, HashMap};
 use std::net::{Ipv4Addr, SocketAddrV4};
 use std::ptr::slice_from_raw_parts;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use uclicious::Uclicious;
-use uclicious::{variable_handlers, Priority, UclError, DEFAULT_DUPLICATE_STRATEGY};
+use uclicious::{
+    variable_handlers, ObjectError, Priority, UclError, UclErrorType, DEFAULT_DUPLICATE_STRATEGY,
+};
 
 #[test]
 fn derive_with_hook() {
@@ -17,7 +22,7 @@ fn derive_with_hook() {
             };
             if var.eq("WWW") {
                 let test = "asd";
-                let size = test.as_bytes().len();
+                let size = test.len();
                 unsafe {
                     *replace = libc::malloc(size).cast();
                     *replace_len = size;
@@ -43,7 +48,7 @@ fn derive_with_hook() {
             };
             if var.eq("ZZZ") {
                 let test = "dsa";
-                let size = test.as_bytes().len();
+                let size = test.len();
                 unsafe {
                     *replace = libc::malloc(size).cast();
                     *replace_len = size;
@@ -99,6 +104,75 @@ fn include_chunk() {
     assert_eq!("asd", test.key_one);
 }
 
+#[test]
+fn include_env_path() {
+    #[derive(Uclicious, Debug)]
+    #[ucl(include(env_path = "UCLICIOUS_TEST_FIXTURES", suffix = "/key_one.ucl"))]
+    struct Test {
+        key_one: String,
+    }
+    std::env::set_var(
+        "UCLICIOUS_TEST_FIXTURES",
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures"),
+    );
+    let test = Test::builder().unwrap().build().unwrap();
+    assert_eq!("asd", test.key_one);
+}
+
+#[test]
+fn include_env_path_missing() {
+    #[derive(Uclicious, Debug)]
+    #[ucl(include(env_path = "UCLICIOUS_TEST_UNSET", suffix = "/key_one.ucl"))]
+    #[allow(dead_code)]
+    struct Test {
+        key_one: String,
+    }
+    std::env::remove_var("UCLICIOUS_TEST_UNSET");
+    let err = Test::builder().err().unwrap();
+    assert_eq!(UclErrorType::Io, err.kind());
+    assert!(err.to_string().contains("UCLICIOUS_TEST_UNSET"));
+}
+
+#[test]
+fn include_chunk_with_register_macro() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use uclicious::raw::RawObject;
+    use uclicious::traits::MacroHandler;
+
+    static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+    fn allow() -> impl MacroHandler {
+        |data: *const u8, len: usize, _args: *const RawObject| {
+            CALLS.fetch_add(1, Ordering::SeqCst);
+            let data = unsafe { std::slice::from_raw_parts(data, len) };
+            data == b"yes"
+        }
+    }
+
+    #[derive(Uclicious, Debug)]
+    #[ucl(parser(register_macro(name = "allow", handler = "allow")))]
+    #[ucl(include(chunk = r#"
+        .allow "yes"
+        key_one = "asd"
+        "#))]
+    struct Test {
+        key_one: String,
+    }
+    let test = Test::builder().unwrap().build().unwrap();
+    assert_eq!("asd", test.key_one);
+    assert_eq!(1, CALLS.load(Ordering::SeqCst));
+
+    let mut builder = Test::builder().unwrap();
+    assert!(builder
+        .add_chunk_full(
+            ".allow \"no\"",
+            Priority::default(),
+            DEFAULT_DUPLICATE_STRATEGY
+        )
+        .is_err());
+    assert_eq!(3, CALLS.load(Ordering::SeqCst));
+}
+
 #[test]
 fn from_str() {
     #[derive(Uclicious, Debug)]
@@ -124,3 +198,1341 @@ fn include_chunk_with_macro() {
     let test = Test::builder().unwrap().build().unwrap();
     assert_eq!("asd", test.key_one);
 }
+
+#[test]
+fn btreemap_field() {
+    #[derive(Uclicious, Debug)]
+    struct Test {
+        gates: BTreeMap<String, bool>,
+    }
+
+    let input = r#"
+        gates {
+            feature_3 = on
+            feature_1 = on
+            feature_2 = off
+        }
+        "#;
+
+    let mut builder = Test::builder().unwrap();
+    builder
+        .add_chunk_full(input, Priority::default(), DEFAULT_DUPLICATE_STRATEGY)
+        .unwrap();
+    let test = builder.build().unwrap();
+
+    let actual: Vec<(&str, bool)> = test.gates.iter().map(|(k, v)| (k.as_str(), *v)).collect();
+    let expected = vec![
+        ("feature_1", true),
+        ("feature_2", false),
+        ("feature_3", true),
+    ];
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn rest_map_dotted_path() {
+    #[derive(Uclicious, Debug)]
+    struct Test {
+        name: String,
+        #[ucl(path = "server.port")]
+        port: i64,
+        #[ucl(rest)]
+        labels: HashMap<String, String>,
+    }
+
+    let input = r#"
+        name = "web"
+        server { port = 80 }
+        team = "infra"
+        tier = "frontend"
+        "#;
+
+    let mut builder = Test::builder().unwrap();
+    builder
+        .add_chunk_full(input, Priority::default(), DEFAULT_DUPLICATE_STRATEGY)
+        .unwrap();
+    let test = builder.build().unwrap();
+
+    assert_eq!("web", test.name);
+    assert_eq!(80, test.port);
+    let mut expected = HashMap::new();
+    expected.insert("team".to_string(), "infra".to_string());
+    expected.insert("tier".to_string(), "frontend".to_string());
+    assert_eq!(expected, test.labels);
+}
+
+#[test]
+fn custom_hasher_map() {
+    use rustc_hash::FxHashMap;
+
+    #[derive(Uclicious, Debug)]
+    struct Test {
+        limits: FxHashMap<String, i64>,
+        #[ucl(rest)]
+        labels: FxHashMap<String, String>,
+    }
+
+    let input = r#"
+        limits { cpu = 2, memory = 512 }
+        team = "infra"
+        "#;
+
+    let mut builder = Test::builder().unwrap();
+    builder
+        .add_chunk_full(input, Priority::default(), DEFAULT_DUPLICATE_STRATEGY)
+        .unwrap();
+    let test = builder.build().unwrap();
+
+    let mut limits = FxHashMap::default();
+    limits.insert("cpu".to_string(), 2);
+    limits.insert("memory".to_string(), 512);
+    assert_eq!(limits, test.limits);
+    let mut labels = FxHashMap::default();
+    labels.insert("team".to_string(), "infra".to_string());
+    assert_eq!(labels, test.labels);
+}
+
+#[test]
+fn flags_from_array() {
+    bitflags::bitflags! {
+        #[derive(Default)]
+        struct Perms: u8 {
+            const READ = 0b001;
+            const WRITE = 0b010;
+            const EXEC = 0b100;
+        }
+    }
+
+    fn perm_from_str(name: &str) -> Option<Perms> {
+        match name {
+            "read" => Some(Perms::READ),
+            "write" => Some(Perms::WRITE),
+            "exec" => Some(Perms::EXEC),
+            _ => None,
+        }
+    }
+
+    #[derive(Uclicious, Debug)]
+    struct Test {
+        #[ucl(flags_from = "perm_from_str")]
+        perms: Perms,
+    }
+
+    let mut builder = Test::builder().unwrap();
+    builder
+        .add_chunk_full(
+            r#"perms = ["read", "write"]"#,
+            Priority::default(),
+            DEFAULT_DUPLICATE_STRATEGY,
+        )
+        .unwrap();
+    let test = builder.build().unwrap();
+    assert_eq!(Perms::READ | Perms::WRITE, test.perms);
+
+    let mut builder = Test::builder().unwrap();
+    builder
+        .add_chunk_full(
+            r#"perms = ["read", "fly"]"#,
+            Priority::default(),
+            DEFAULT_DUPLICATE_STRATEGY,
+        )
+        .unwrap();
+    let err = builder.build().unwrap_err();
+    assert!(err.to_string().contains("unknown flag `fly`"));
+}
+
+#[test]
+fn split_delimited_string() {
+    #[derive(Uclicious, Debug)]
+    struct Test {
+        #[ucl(split = ",")]
+        tags: Vec<String>,
+    }
+
+    let mut builder = Test::builder().unwrap();
+    builder
+        .add_chunk_full(
+            r#"tags = "a, b ,c,""#,
+            Priority::default(),
+            DEFAULT_DUPLICATE_STRATEGY,
+        )
+        .unwrap();
+    let test = builder.build().unwrap();
+    assert_eq!(vec!["a", "b", "c"], test.tags);
+
+    let mut builder = Test::builder().unwrap();
+    builder
+        .add_chunk_full(
+            r#"tags = ["a,b", "c"]"#,
+            Priority::default(),
+            DEFAULT_DUPLICATE_STRATEGY,
+        )
+        .unwrap();
+    let test = builder.build().unwrap();
+    assert_eq!(vec!["a,b", "c"], test.tags);
+}
+
+#[test]
+fn programmatic_setters() {
+    #[derive(Uclicious, Debug)]
+    #[ucl(programmatic_setters)]
+    struct Test {
+        name: String,
+        #[ucl(path = "server.port")]
+        port: i64,
+        #[ucl(path = "server.host")]
+        host: String,
+        #[ucl(default)]
+        debug: bool,
+    }
+
+    let input = r#"
+        name = "web"
+        server { port = 80, host = "localhost" }
+        "#;
+
+    let mut builder = Test::builder().unwrap();
+    builder
+        .add_chunk_full(input, Priority::default(), DEFAULT_DUPLICATE_STRATEGY)
+        .unwrap();
+    let test = builder
+        .with_port(8000)
+        .unwrap()
+        .with_port(8080)
+        .unwrap()
+        .with_name("api".to_string())
+        .unwrap()
+        .build()
+        .unwrap();
+
+    assert_eq!("api", test.name);
+    assert_eq!(8080, test.port);
+    assert_eq!("localhost", test.host);
+    assert!(!test.debug);
+}
+
+#[test]
+fn programmatic_setters_field_types() {
+    #[derive(Uclicious, Debug)]
+    #[ucl(programmatic_setters)]
+    struct Test {
+        port: u16,
+        #[ucl(default)]
+        name: Option<String>,
+        #[ucl(default)]
+        tags: Vec<String>,
+        timeout: Duration,
+        #[ucl(from_str, skip_setter)]
+        addr: Ipv4Addr,
+    }
+
+    let input = r#"
+        port = 80
+        name = web
+        timeout = 10s
+        addr = "127.0.0.1"
+        "#;
+
+    let mut builder = Test::builder().unwrap();
+    builder
+        .add_chunk_full(input, Priority::default(), DEFAULT_DUPLICATE_STRATEGY)
+        .unwrap();
+    let test = builder
+        .with_port(8080)
+        .unwrap()
+        .with_name(None)
+        .unwrap()
+        .with_tags(vec!["a".to_string(), "b".to_string()])
+        .unwrap()
+        .with_timeout(Duration::from_millis(1500))
+        .unwrap()
+        .build()
+        .unwrap();
+
+    assert_eq!(8080, test.port);
+    assert_eq!(None, test.name);
+    assert_eq!(vec!["a", "b"], test.tags);
+    assert_eq!(Duration::from_millis(1500), test.timeout);
+    assert_eq!(Ipv4Addr::LOCALHOST, test.addr);
+}
+
+#[test]
+fn enum_derive() {
+    #[derive(Uclicious, Debug, Eq, PartialEq)]
+    #[ucl(rename_all = "snake_case")]
+    enum Level {
+        Debug,
+        WarnOnly,
+        #[ucl(rename = "err")]
+        Error,
+    }
+
+    #[derive(Uclicious, Debug)]
+    struct Test {
+        level: Level,
+        levels: Vec<Level>,
+    }
+
+    let input = r#"
+        level = "warn_only"
+        levels = ["debug", "err"]
+        "#;
+
+    let mut builder = Test::builder().unwrap();
+    builder
+        .add_chunk_full(input, Priority::default(), DEFAULT_DUPLICATE_STRATEGY)
+        .unwrap();
+    let test = builder.build().unwrap();
+    assert_eq!(Level::WarnOnly, test.level);
+    assert_eq!(vec![Level::Debug, Level::Error], test.levels);
+
+    let mut builder = Test::builder().unwrap();
+    builder
+        .add_chunk_full(
+            "level = error\nlevels = []",
+            Priority::default(),
+            DEFAULT_DUPLICATE_STRATEGY,
+        )
+        .unwrap();
+    let err = builder.build().unwrap_err();
+    assert!(err.to_string().contains("unknown variant `error`"));
+}
+
+#[test]
+fn arc_str_field() {
+    #[derive(Uclicious, Debug)]
+    struct Test {
+        service: Arc<str>,
+        aliases: Vec<Arc<str>>,
+        owner: std::rc::Rc<str>,
+        team: std::borrow::Cow<'static, str>,
+    }
+
+    let input = r#"
+        service = "billing"
+        aliases = ["invoices", "payments"]
+        owner = "finance"
+        team = "payments"
+        "#;
+
+    let mut builder = Test::builder().unwrap();
+    builder
+        .add_chunk_full(input, Priority::default(), DEFAULT_DUPLICATE_STRATEGY)
+        .unwrap();
+    let test = builder.build().unwrap();
+
+    assert_eq!("billing", &*test.service);
+    let shared = Arc::clone(&test.service);
+    assert_eq!(2, Arc::strong_count(&shared));
+    let aliases: Vec<&str> = test.aliases.iter().map(|alias| &**alias).collect();
+    assert_eq!(vec!["invoices", "payments"], aliases);
+    assert_eq!("finance", &*test.owner);
+    assert_eq!("payments", test.team);
+}
+
+#[test]
+fn deprecated_field() {
+    #[derive(Uclicious, Debug)]
+    struct Test {
+        #[ucl(default)]
+        listen: Option<String>,
+        #[ucl(default, deprecated = "use `listen` instead")]
+        bind: Option<String>,
+    }
+
+    let notices = Arc::new(Mutex::new(Vec::new()));
+    let sink = {
+        let notices = Arc::clone(&notices);
+        move |path: &str, message: &str| {
+            notices
+                .lock()
+                .unwrap()
+                .push(format!("{}: {}", path, message));
+        }
+    };
+
+    let mut builder = Test::builder().unwrap();
+    builder.set_deprecation_sink(Box::new(sink.clone()));
+    builder
+        .add_chunk_full(
+            "bind = \"0.0.0.0\"",
+            Priority::default(),
+            DEFAULT_DUPLICATE_STRATEGY,
+        )
+        .unwrap();
+    let test = builder.build().unwrap();
+    assert_eq!(Some("0.0.0.0".to_string()), test.bind);
+    assert_eq!(
+        vec!["bind: use `listen` instead".to_string()],
+        *notices.lock().unwrap()
+    );
+
+    let mut builder = Test::builder().unwrap();
+    builder.set_deprecation_sink(Box::new(sink));
+    builder
+        .add_chunk_full(
+            "listen = \"0.0.0.0\"",
+            Priority::default(),
+            DEFAULT_DUPLICATE_STRATEGY,
+        )
+        .unwrap();
+    let test = builder.build().unwrap();
+    assert_eq!(Some("0.0.0.0".to_string()), test.listen);
+    assert_eq!(1, notices.lock().unwrap().len());
+}
+
+#[test]
+fn skip_field() {
+    #[derive(Uclicious, Debug)]
+    struct Test {
+        name: String,
+        #[ucl(skip, default)]
+        runtime: Arc<Mutex<Vec<String>>>,
+        #[ucl(skip, default = "3")]
+        retries: u8,
+    }
+
+    let input = r#"
+        name = "worker"
+        runtime = "should be ignored"
+        retries = 10
+        "#;
+
+    let mut builder = Test::builder().unwrap();
+    builder
+        .add_chunk_full(input, Priority::default(), DEFAULT_DUPLICATE_STRATEGY)
+        .unwrap();
+    let test = builder.build().unwrap();
+
+    assert_eq!("worker", test.name);
+    assert!(test.runtime.lock().unwrap().is_empty());
+    assert_eq!(3, test.retries);
+}
+
+#[test]
+fn flatten_struct() {
+    #[derive(Uclicious, Debug)]
+    #[ucl(skip_builder)]
+    struct TlsConfig {
+        cert: String,
+        key: String,
+    }
+
+    #[derive(Uclicious, Debug)]
+    struct Test {
+        port: i64,
+        #[ucl(flatten)]
+        tls: TlsConfig,
+    }
+
+    let input = r#"
+        port = 443
+        cert = "/etc/ssl/server.crt"
+        key = "/etc/ssl/server.key"
+        "#;
+
+    let mut builder = Test::builder().unwrap();
+    builder
+        .add_chunk_full(input, Priority::default(), DEFAULT_DUPLICATE_STRATEGY)
+        .unwrap();
+    let test = builder.build().unwrap();
+
+    assert_eq!(443, test.port);
+    assert_eq!("/etc/ssl/server.crt", test.tls.cert);
+    assert_eq!("/etc/ssl/server.key", test.tls.key);
+}
+
+#[test]
+fn rename_all_fields() {
+    #[derive(Uclicious, Debug)]
+    #[ucl(rename_all = "kebab-case")]
+    struct Test {
+        max_connections: i64,
+        #[ucl(path = "timeout_ms")]
+        timeout: i64,
+    }
+
+    #[derive(Uclicious, Debug)]
+    #[ucl(rename_all = "camelCase")]
+    struct Camel {
+        max_connections: i64,
+    }
+
+    let input = r#"
+        max-connections = 5
+        maxConnections = 7
+        timeout_ms = 100
+        "#;
+
+    let mut builder = Test::builder().unwrap();
+    builder
+        .add_chunk_full(input, Priority::default(), DEFAULT_DUPLICATE_STRATEGY)
+        .unwrap();
+    let test = builder.build().unwrap();
+    assert_eq!(5, test.max_connections);
+    assert_eq!(100, test.timeout);
+
+    let mut builder = Camel::builder().unwrap();
+    builder
+        .add_chunk_full(input, Priority::default(), DEFAULT_DUPLICATE_STRATEGY)
+        .unwrap();
+    assert_eq!(7, builder.build().unwrap().max_connections);
+}
+
+#[test]
+fn rest_map() {
+    type Extra = HashMap<String, String>;
+
+    #[derive(Uclicious, Debug)]
+    struct Test {
+        name: String,
+        #[ucl(rest)]
+        rest: Extra,
+    }
+
+    let input = r#"
+        name = "web"
+        owner = "infra"
+        region = "eu-west-1"
+        "#;
+
+    let mut builder = Test::builder().unwrap();
+    builder
+        .add_chunk_full(input, Priority::default(), DEFAULT_DUPLICATE_STRATEGY)
+        .unwrap();
+    let test = builder.build().unwrap();
+
+    assert_eq!("web", test.name);
+    assert_eq!(2, test.rest.len());
+    assert_eq!("infra", test.rest["owner"]);
+    assert_eq!("eu-west-1", test.rest["region"]);
+}
+
+#[test]
+fn build_fn_validate() {
+    #[derive(Uclicious, Debug)]
+    #[ucl(build_fn(validate = "Range::check"))]
+    struct Range {
+        start: i64,
+        end: i64,
+    }
+
+    impl Range {
+        fn check(range: &Range) -> Result<(), String> {
+            if range.start < range.end {
+                Ok(())
+            } else {
+                Err(format!(
+                    "start {} is not before end {}",
+                    range.start, range.end
+                ))
+            }
+        }
+    }
+
+    let mut builder = Range::builder().unwrap();
+    builder
+        .add_chunk_full(
+            "start = 1\nend = 10",
+            Priority::default(),
+            DEFAULT_DUPLICATE_STRATEGY,
+        )
+        .unwrap();
+    let range = builder.build().unwrap();
+    assert_eq!((1, 10), (range.start, range.end));
+
+    let mut builder = Range::builder().unwrap();
+    builder
+        .add_chunk_full(
+            "start = 10\nend = 1",
+            Priority::default(),
+            DEFAULT_DUPLICATE_STRATEGY,
+        )
+        .unwrap();
+    let err = builder.build().unwrap_err();
+    assert_eq!("start 10 is not before end 1", err.to_string());
+}
+
+#[test]
+fn build_error_is_send_sync() {
+    fn assert_send_sync<T: Send + Sync>(_: &T) {}
+
+    #[allow(dead_code)]
+    #[derive(Uclicious, Debug)]
+    struct Test {
+        port: u16,
+    }
+
+    let builder = Test::builder().unwrap();
+    let err = builder.build().unwrap_err();
+    assert_send_sync(&err);
+    assert_send_sync(&UclError::new(UclErrorType::Other, "error"));
+    assert_send_sync(&ObjectError::other("error"));
+}
+
+#[test]
+fn path_into_array() {
+    #[derive(Uclicious, Debug)]
+    struct Test {
+        #[ucl(path = "servers.0.host")]
+        first_host: String,
+        #[ucl(path = "servers.1.port", default)]
+        second_port: Option<u16>,
+    }
+
+    let mut builder = Test::builder().unwrap();
+    builder
+        .add_chunk_full(
+            r#"servers = [{ host = "a.example.com", port = 80 }, { host = "b.example.com" }]"#,
+            Priority::default(),
+            DEFAULT_DUPLICATE_STRATEGY,
+        )
+        .unwrap();
+    let test = builder.build().unwrap();
+    assert_eq!("a.example.com", test.first_host);
+    assert_eq!(None, test.second_port);
+}
+
+#[test]
+fn builder_register_variables() {
+    #[derive(Uclicious, Debug)]
+    struct Test {
+        url: String,
+    }
+
+    let mut vars = HashMap::new();
+    vars.insert("host", "localhost");
+    vars.insert("port", "8080");
+    let mut builder = Test::builder().unwrap();
+    builder.register_variables(&vars);
+    builder
+        .add_chunk_full(
+            r#"url = "http://$host:$port""#,
+            Priority::default(),
+            DEFAULT_DUPLICATE_STRATEGY,
+        )
+        .unwrap();
+    let test = builder.build().unwrap();
+    assert_eq!("http://localhost:8080", test.url);
+}
+
+#[test]
+fn track_presence() {
+    use uclicious::raw::parser::FieldSource;
+
+    #[derive(Uclicious, Debug)]
+    #[ucl(track_presence)]
+    struct Test {
+        name: String,
+        #[ucl(default = "8080")]
+        port: u16,
+        #[ucl(path = "tls.enabled", default)]
+        tls: bool,
+        #[ucl(skip)]
+        computed: u8,
+    }
+
+    let mut builder = Test::builder().unwrap();
+    builder
+        .add_chunk_full(
+            "name = web\ntls { enabled = yes }",
+            Priority::default(),
+            DEFAULT_DUPLICATE_STRATEGY,
+        )
+        .unwrap();
+    let (test, sources) = builder.build_with_sources().unwrap();
+    assert_eq!(3, sources.len());
+    assert_eq!(FieldSource::Config, sources["name"]);
+    assert_eq!(FieldSource::Default, sources["port"]);
+    assert_eq!(FieldSource::Config, sources["tls"]);
+
+    assert_eq!(8080, test.port);
+    assert!(test.tls);
+    assert_eq!("web", test.name);
+    assert_eq!(0, test.computed);
+}
+
+#[test]
+fn track_presence_env_path() {
+    use uclicious::raw::parser::FieldSource;
+
+    #[derive(Uclicious, Debug)]
+    #[ucl(track_presence)]
+    #[ucl(include(env_path = "UCLICIOUS_TEST_PRESENCE_FIXTURES", suffix = "/key_one.ucl"))]
+    struct Test {
+        key_one: String,
+        name: String,
+        #[ucl(default)]
+        port: u16,
+    }
+    std::env::set_var(
+        "UCLICIOUS_TEST_PRESENCE_FIXTURES",
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures"),
+    );
+
+    let mut builder = Test::builder().unwrap();
+    builder
+        .add_chunk_full(
+            "name = web\n",
+            Priority::default(),
+            DEFAULT_DUPLICATE_STRATEGY,
+        )
+        .unwrap();
+    let (test, sources) = builder.build_with_sources().unwrap();
+    assert_eq!("asd", test.key_one);
+    assert_eq!("web", test.name);
+    assert_eq!(0, test.port);
+    assert_eq!(FieldSource::Env, sources["key_one"]);
+    assert_eq!(FieldSource::Config, sources["name"]);
+    assert_eq!(FieldSource::Default, sources["port"]);
+
+    // Same value from a later source replaces the one from the file.
+    let mut builder = Test::builder().unwrap();
+    builder
+        .add_chunk_full(
+            "name = web\nkey_one = asd\n",
+            Priority::new(1),
+            DEFAULT_DUPLICATE_STRATEGY,
+        )
+        .unwrap();
+    let (_, sources) = builder.build_with_sources().unwrap();
+    assert_eq!(FieldSource::Config, sources["key_one"]);
+}
+
+#[test]
+fn path_sep() {
+    #[derive(Uclicious, Debug)]
+    #[ucl(path_sep = "/")]
+    struct Test {
+        #[ucl(path = "domains/example.com/port")]
+        port: u16,
+        #[ucl(path = "server/name")]
+        name: String,
+    }
+
+    #[derive(Uclicious, Debug)]
+    struct Dotted {
+        #[ucl(path = "domains.example.com.port", default)]
+        port: Option<u16>,
+    }
+
+    let input = r#"
+        domains { "example.com" { port = 443 } }
+        server { name = "web" }
+        "#;
+    let mut builder = Test::builder().unwrap();
+    builder
+        .add_chunk_full(input, Priority::default(), DEFAULT_DUPLICATE_STRATEGY)
+        .unwrap();
+    let test = builder.build().unwrap();
+    assert_eq!(443, test.port);
+    assert_eq!("web", test.name);
+
+    let mut builder = Dotted::builder().unwrap();
+    builder
+        .add_chunk_full(input, Priority::default(), DEFAULT_DUPLICATE_STRATEGY)
+        .unwrap();
+    assert_eq!(None, builder.build().unwrap().port);
+}
+
+#[test]
+fn vec_of_derived_structs() {
+    #[derive(Uclicious, Debug, PartialEq)]
+    #[ucl(skip_builder)]
+    struct Server {
+        host: String,
+        #[ucl(default = "80")]
+        port: u16,
+    }
+
+    #[derive(Uclicious, Debug)]
+    struct Test {
+        servers: Vec<Server>,
+        backup: Vec<Server>,
+    }
+
+    let input = r#"
+        servers = [
+            { host = "a.example.com" },
+            { host = "b.example.com", port = 8080 },
+        ]
+        backup { host = "c.example.com" }
+        backup { host = "d.example.com" }
+        "#;
+    let mut builder = Test::builder().unwrap();
+    builder
+        .add_chunk_full(input, Priority::default(), DEFAULT_DUPLICATE_STRATEGY)
+        .unwrap();
+    let test = builder.build().unwrap();
+
+    assert_eq!(
+        vec![
+            Server {
+                host: "a.example.com".to_string(),
+                port: 80
+            },
+            Server {
+                host: "b.example.com".to_string(),
+                port: 8080
+            },
+        ],
+        test.servers
+    );
+    let backup: Vec<&str> = test.backup.iter().map(|s| s.host.as_str()).collect();
+    assert_eq!(vec!["c.example.com", "d.example.com"], backup);
+}
+
+#[test]
+fn map_of_derived_structs() {
+    #[derive(Uclicious, Debug, PartialEq)]
+    #[ucl(skip_builder)]
+    struct Pool {
+        size: u32,
+    }
+
+    #[derive(Uclicious, Debug)]
+    struct Test {
+        pools: HashMap<String, Pool>,
+        sorted: BTreeMap<String, Pool>,
+    }
+
+    let input = r#"
+        pools { a { size = 1 }, b { size = 2 } }
+        sorted { c { size = 3 } }
+        "#;
+    let mut builder = Test::builder().unwrap();
+    builder
+        .add_chunk_full(input, Priority::default(), DEFAULT_DUPLICATE_STRATEGY)
+        .unwrap();
+    let test = builder.build().unwrap();
+
+    assert_eq!(2, test.pools.len());
+    assert_eq!(Pool { size: 1 }, test.pools["a"]);
+    assert_eq!(Pool { size: 2 }, test.pools["b"]);
+    assert_eq!(Pool { size: 3 }, test.sorted["c"]);
+}
+
+#[test]
+fn option_explicit_null() {
+    #[derive(Uclicious, Debug)]
+    struct Test {
+        #[ucl(default)]
+        option: Option<String>,
+        #[ucl(default)]
+        missing: Option<String>,
+    }
+
+    let mut builder = Test::builder().unwrap();
+    builder
+        .add_chunk_full(
+            "option = null",
+            Priority::default(),
+            DEFAULT_DUPLICATE_STRATEGY,
+        )
+        .unwrap();
+    let test = builder.build().unwrap();
+
+    assert_eq!(None, test.option);
+    assert_eq!(None, test.missing);
+}
+
+#[test]
+fn default_fn_error() {
+    fn port() -> Result<u16, ObjectError> {
+        Ok(80)
+    }
+    fn token() -> Result<String, ObjectError> {
+        Err(ObjectError::other("token is not configured"))
+    }
+
+    #[derive(Uclicious, Debug)]
+    struct Fallback {
+        #[ucl(default_fn = "port")]
+        port: u16,
+    }
+
+    #[derive(Uclicious, Debug)]
+    struct Failing {
+        #[ucl(default_fn = "token")]
+        token: String,
+    }
+
+    let mut builder = Fallback::builder().unwrap();
+    builder
+        .add_chunk_full("", Priority::default(), DEFAULT_DUPLICATE_STRATEGY)
+        .unwrap();
+    assert_eq!(80, builder.build().unwrap().port);
+
+    let mut builder = Failing::builder().unwrap();
+    builder
+        .add_chunk_full(
+            "token = abc",
+            Priority::default(),
+            DEFAULT_DUPLICATE_STRATEGY,
+        )
+        .unwrap();
+    assert_eq!("abc", builder.build().unwrap().token);
+
+    let mut builder = Failing::builder().unwrap();
+    builder
+        .add_chunk_full("", Priority::default(), DEFAULT_DUPLICATE_STRATEGY)
+        .unwrap();
+    let err = builder.build().unwrap_err();
+    assert!(err.to_string().contains("token is not configured"));
+}
+
+#[test]
+fn validate_custom_error() {
+    #[derive(Debug)]
+    enum PortError {
+        Reserved(u16),
+    }
+
+    impl std::fmt::Display for PortError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                PortError::Reserved(port) => write!(f, "port {} is reserved", port),
+            }
+        }
+    }
+
+    fn not_reserved(_lookup_path: &str, port: &u16) -> Result<(), PortError> {
+        if *port < 1024 {
+            Err(PortError::Reserved(*port))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn not_zero(lookup_path: &str, workers: &u32) -> Result<(), ObjectError> {
+        if *workers == 0 {
+            Err(ObjectError::KeyNotFound(lookup_path.to_string()))
+        } else {
+            Ok(())
+        }
+    }
+
+    #[derive(Uclicious, Debug)]
+    #[ucl(skip_builder)]
+    struct Listen {
+        #[ucl(validate = "not_reserved")]
+        port: u16,
+        #[ucl(validate = "not_zero")]
+        workers: u32,
+    }
+
+    let parse = |input: &str| {
+        let mut parser = uclicious::Parser::default();
+        parser
+            .add_chunk_full(input, Priority::default(), DEFAULT_DUPLICATE_STRATEGY)
+            .unwrap();
+        let listen = parser.get_object().unwrap().lookup("listen").unwrap();
+        <Listen as uclicious::FromObject<_>>::try_from(listen)
+    };
+
+    let listen = parse("listen { port = 8080, workers = 4 }").unwrap();
+    assert_eq!(8080, listen.port);
+    assert_eq!(4, listen.workers);
+    let err = parse("listen { port = 80, workers = 4 }").unwrap_err();
+    assert_eq!(ObjectError::Other("port 80 is reserved".to_string()), err);
+    let err = parse("listen { port = 8080, workers = 0 }").unwrap_err();
+    assert_eq!(ObjectError::KeyNotFound("workers".to_string()), err);
+}
+
+#[test]
+fn parse_with() {
+    fn hex(src: &str) -> Result<u32, std::num::ParseIntError> {
+        u32::from_str_radix(src.trim_start_matches("0x"), 16)
+    }
+
+    #[derive(Uclicious, Debug)]
+    struct Test {
+        #[ucl(parse_with = "hex")]
+        mask: u32,
+    }
+
+    let mut builder = Test::builder().unwrap();
+    builder
+        .add_chunk_full(
+            "mask = \"0xFF\"",
+            Priority::default(),
+            DEFAULT_DUPLICATE_STRATEGY,
+        )
+        .unwrap();
+    assert_eq!(255, builder.build().unwrap().mask);
+
+    let mut builder = Test::builder().unwrap();
+    builder
+        .add_chunk_full(
+            "mask = \"0xZZ\"",
+            Priority::default(),
+            DEFAULT_DUPLICATE_STRATEGY,
+        )
+        .unwrap();
+    assert!(builder.build().is_err());
+}
+
+#[test]
+fn boxed_field() {
+    #[derive(Uclicious, Debug)]
+    #[ucl(skip_builder)]
+    struct Extra {
+        enabled: bool,
+    }
+
+    #[derive(Uclicious, Debug)]
+    struct Test {
+        inner: Box<Extra>,
+        #[ucl(default)]
+        next: Option<Box<Extra>>,
+    }
+
+    let mut builder = Test::builder().unwrap();
+    builder
+        .add_chunk_full(
+            "inner { enabled = yes }",
+            Priority::default(),
+            DEFAULT_DUPLICATE_STRATEGY,
+        )
+        .unwrap();
+    let test = builder.build().unwrap();
+
+    assert!(test.inner.enabled);
+    assert!(test.next.is_none());
+}
+
+#[test]
+fn os_string_and_optional_path() {
+    #[derive(Uclicious, Debug)]
+    struct Test {
+        executable: std::ffi::OsString,
+        #[ucl(default)]
+        log_file: Option<std::path::PathBuf>,
+        #[ucl(default)]
+        pid_file: Option<std::path::PathBuf>,
+    }
+
+    let input = r#"
+        executable = "nginx"
+        log_file = null
+        pid_file = "/var/run/nginx.pid"
+        "#;
+    let mut builder = Test::builder().unwrap();
+    builder
+        .add_chunk_full(input, Priority::default(), DEFAULT_DUPLICATE_STRATEGY)
+        .unwrap();
+    let test = builder.build().unwrap();
+
+    assert_eq!("nginx", test.executable);
+    assert_eq!(None, test.log_file);
+    assert_eq!(
+        Some(std::path::PathBuf::from("/var/run/nginx.pid")),
+        test.pid_file
+    );
+}
+
+#[test]
+fn getters() {
+    mod config {
+        use uclicious::*;
+
+        #[derive(Uclicious, Debug)]
+        #[ucl(getters)]
+        pub struct Test {
+            name: String,
+            #[ucl(path = "listen.port")]
+            port: u16,
+        }
+    }
+
+    let mut builder = config::Test::builder().unwrap();
+    builder
+        .add_chunk_full(
+            "name = web\nlisten { port = 8080 }",
+            Priority::default(),
+            DEFAULT_DUPLICATE_STRATEGY,
+        )
+        .unwrap();
+    let test = builder.build().unwrap();
+    assert_eq!("web", test.name());
+    assert_eq!(&8080, test.port());
+}
+
+#[test]
+fn from_parsed_object() {
+    #[derive(Uclicious, Debug)]
+    struct Listen {
+        host: String,
+        #[ucl(default = "8080")]
+        port: u16,
+    }
+
+    let mut parser = uclicious::Parser::default();
+    parser
+        .add_chunk_full(
+            "server { listen { host = localhost } }",
+            Priority::default(),
+            DEFAULT_DUPLICATE_STRATEGY,
+        )
+        .unwrap();
+    let root = parser.get_object().unwrap();
+    let listen = root.lookup_path("server.listen").unwrap();
+    let listen = Listen::from_object(&listen).unwrap();
+    assert_eq!("localhost", listen.host);
+    assert_eq!(8080, listen.port);
+
+    let server = root.lookup("server").unwrap();
+    let err = Listen::from_object(&server).unwrap_err();
+    assert!(err.to_string().contains("host"), "{}", err);
+}
+
+#[test]
+fn parser_flags_inline() {
+    #[derive(Uclicious, Debug)]
+    #[ucl(parser(flags(key_lowercase, no_time)))]
+    struct Test {
+        server_name: String,
+        timeout: String,
+    }
+
+    let mut builder = Test::builder().unwrap();
+    builder
+        .add_chunk_full(
+            "Server_Name = web\nTIMEOUT = 10s",
+            Priority::default(),
+            DEFAULT_DUPLICATE_STRATEGY,
+        )
+        .unwrap();
+    let test = builder.build().unwrap();
+    assert_eq!("web", test.server_name);
+    assert_eq!("10s", test.timeout);
+}
+
+#[test]
+fn build_ref() {
+    #[derive(Uclicious, Debug)]
+    struct Test {
+        name: String,
+        #[ucl(default = "8080")]
+        port: u16,
+    }
+
+    let mut builder = Test::builder().unwrap();
+    builder
+        .add_chunk_full(
+            "name = web\n",
+            Priority::default(),
+            DEFAULT_DUPLICATE_STRATEGY,
+        )
+        .unwrap();
+    let first = builder.build_ref().unwrap();
+    assert_eq!("web", first.name);
+    assert_eq!(8080, first.port);
+
+    builder
+        .add_chunk_full("port = 80", Priority::default(), DEFAULT_DUPLICATE_STRATEGY)
+        .unwrap();
+    let second = builder.build_ref().unwrap();
+    assert_eq!("web", second.name);
+    assert_eq!(80, second.port);
+
+    let third = builder.build().unwrap();
+    assert_eq!(80, third.port);
+}
+
+#[test]
+fn float_from_int_attribute() {
+    #[derive(Debug, PartialEq)]
+    struct Ratio(f64);
+
+    impl From<f64> for Ratio {
+        fn from(value: f64) -> Self {
+            Ratio(value)
+        }
+    }
+
+    fn positive(_: &str, value: &f64) -> Result<(), String> {
+        if *value > 0.0 {
+            Ok(())
+        } else {
+            Err("must be positive".to_string())
+        }
+    }
+
+    #[derive(Uclicious, Debug)]
+    struct Test {
+        #[ucl(float_from_int)]
+        ratio: Ratio,
+        #[ucl(float_from_int, default)]
+        scale: Option<f64>,
+        #[ucl(float_from_int, validate = "positive")]
+        weight: f64,
+    }
+
+    let mut builder = Test::builder().unwrap();
+    builder
+        .add_chunk_full(
+            "ratio = 1\nscale = 2\nweight = 0.5",
+            Priority::default(),
+            DEFAULT_DUPLICATE_STRATEGY,
+        )
+        .unwrap();
+    let test = builder.build().unwrap();
+    assert_eq!(Ratio(1.0), test.ratio);
+    assert_eq!(Some(2.0), test.scale);
+    assert_eq!(0.5, test.weight);
+
+    let mut builder = Test::builder().unwrap();
+    builder
+        .add_chunk_full(
+            "ratio = yes\nweight = 1",
+            Priority::default(),
+            DEFAULT_DUPLICATE_STRATEGY,
+        )
+        .unwrap();
+    assert!(builder.build().is_err());
+}
+
+#[test]
+fn float_field_from_int() {
+    #[derive(Uclicious, Debug)]
+    struct Test {
+        pi: f64,
+        ratio: Option<f64>,
+    }
+
+    let mut builder = Test::builder().unwrap();
+    builder
+        .add_chunk_full(
+            "pi = 3\nratio = 0.25",
+            Priority::default(),
+            DEFAULT_DUPLICATE_STRATEGY,
+        )
+        .unwrap();
+    let test = builder.build().unwrap();
+    assert_eq!(3.0, test.pi);
+    assert_eq!(Some(0.25), test.ratio);
+}
+
+#[test]
+fn clonable_builder() {
+    #[derive(Uclicious, Debug)]
+    #[ucl(clonable_builder)]
+    #[ucl(var(name = "name", value = "base"))]
+    struct Test {
+        name: String,
+        port: i64,
+        interval: std::time::Duration,
+    }
+
+    let mut base = Test::builder().unwrap();
+    base.add_chunk_full(
+        "name = $name\nport = 80\ninterval = 10s\n",
+        Priority::default(),
+        DEFAULT_DUPLICATE_STRATEGY,
+    )
+    .unwrap();
+
+    let mut left = base.clone();
+    left.add_chunk_full(
+        "port = 8080\n",
+        Priority::new(1),
+        DEFAULT_DUPLICATE_STRATEGY,
+    )
+    .unwrap();
+    let mut right = base.clone();
+    right
+        .add_chunk_full(
+            "name = $name-right\n",
+            Priority::new(1),
+            DEFAULT_DUPLICATE_STRATEGY,
+        )
+        .unwrap();
+
+    let left = left.build().unwrap();
+    assert_eq!("base", left.name);
+    assert_eq!(8080, left.port);
+    assert_eq!(std::time::Duration::from_secs(10), left.interval);
+
+    let right = right.build().unwrap();
+    assert_eq!("base-right", right.name);
+    assert_eq!(80, right.port);
+
+    let base = base.build().unwrap();
+    assert_eq!("base", base.name);
+    assert_eq!(80, base.port);
+}
+
+#[test]
+fn clonable_builder_copies_parsed_values() {
+    #[derive(Uclicious, Debug)]
+    #[ucl(clonable_builder)]
+    #[ucl(include(chunk = "port = 80\n"))]
+    struct Test {
+        name: String,
+        port: i64,
+        #[ucl(default)]
+        tags: Vec<String>,
+    }
+
+    let path = std::env::temp_dir().join("uclicious_clonable_builder");
+    std::fs::write(
+        &path,
+        "name = \"file \\\"quoted\\\"\"\ntags = a\ntags = b\n",
+    )
+    .unwrap();
+
+    let mut base = Test::builder().unwrap();
+    base.add_file_full(&path, Priority::new(5), DEFAULT_DUPLICATE_STRATEGY)
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let mut clone = base.clone();
+    clone
+        .add_chunk_full(
+            "name = clone\nport = 8080\n",
+            Priority::new(1),
+            DEFAULT_DUPLICATE_STRATEGY,
+        )
+        .unwrap();
+    let clone = clone.build().unwrap();
+    assert_eq!("file \"quoted\"", clone.name);
+    assert_eq!(8080, clone.port);
+    assert_eq!(vec!["a", "b"], clone.tags);
+
+    let base = base.build().unwrap();
+    assert_eq!("file \"quoted\"", base.name);
+    assert_eq!(80, base.port);
+}
+
+#[test]
+fn struct_level_default() {
+    #[derive(Uclicious, Debug)]
+    #[ucl(default)]
+    struct Test {
+        name: String,
+        port: i64,
+    }
+
+    impl Default for Test {
+        fn default() -> Self {
+            Test {
+                name: "web".to_string(),
+                port: 80,
+            }
+        }
+    }
+
+    let mut builder = Test::builder().unwrap();
+    builder
+        .add_chunk_full(
+            "port = 8080",
+            Priority::default(),
+            DEFAULT_DUPLICATE_STRATEGY,
+        )
+        .unwrap();
+    let test = builder.build().unwrap();
+
+    assert_eq!("web", test.name);
+    assert_eq!(8080, test.port);
+}
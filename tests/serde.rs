@@ -0,0 +1,80 @@
+#![cfg(feature = "serde")]
+use serde::Deserialize;
+use std::collections::HashMap;
+use uclicious::raw::ObjectDeserializer;
+use uclicious::{Parser, Priority, DEFAULT_DUPLICATE_STRATEGY};
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Listener {
+    address: String,
+    port: u16,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Config {
+    name: String,
+    listeners: Vec<Listener>,
+    limits: HashMap<String, u64>,
+    upstreams: HashMap<String, Vec<String>>,
+}
+
+#[test]
+fn deserialize_nested_maps_and_vecs() {
+    let input = r#"
+    name = "proxy"
+    listeners = [
+        { address = "0.0.0.0", port = 80 },
+        { address = "::", port = 443 },
+    ]
+    limits {
+        connections = 1024
+        body = 1mb
+    }
+    upstreams {
+        web = ["10.0.0.1", "10.0.0.2"]
+        api = "10.0.1.1"
+    }
+    "#;
+    let mut parser = Parser::default();
+    parser
+        .add_chunk_full(input, Priority::default(), DEFAULT_DUPLICATE_STRATEGY)
+        .unwrap();
+    let root = parser.get_object().unwrap();
+
+    let config = Config::deserialize(ObjectDeserializer::new(&root)).unwrap();
+
+    assert_eq!("proxy", config.name);
+    assert_eq!(
+        vec![
+            Listener {
+                address: "0.0.0.0".to_string(),
+                port: 80
+            },
+            Listener {
+                address: "::".to_string(),
+                port: 443
+            },
+        ],
+        config.listeners
+    );
+    assert_eq!(1024, config.limits["connections"]);
+    assert_eq!(1024 * 1024, config.limits["body"]);
+    assert_eq!(vec!["10.0.0.1", "10.0.0.2"], config.upstreams["web"]);
+    assert_eq!(vec!["10.0.1.1"], config.upstreams["api"]);
+}
+
+#[test]
+fn deserialize_missing_field() {
+    let mut parser = Parser::default();
+    parser
+        .add_chunk_full(
+            "name = proxy",
+            Priority::default(),
+            DEFAULT_DUPLICATE_STRATEGY,
+        )
+        .unwrap();
+    let root = parser.get_object().unwrap();
+
+    let err = Config::deserialize(ObjectDeserializer::new(&root)).unwrap_err();
+    assert!(err.to_string().contains("listeners"));
+}
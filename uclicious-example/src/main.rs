@@ -33,6 +33,7 @@ impl TryFrom<String> for Visibility {
         }
     }
 }
+#[allow(dead_code)]
 #[derive(Debug, Uclicious)]
 #[ucl(var(name = "test", value = "works"))]
 #[ucl(include(path = "test.ucl"))]
@@ -64,6 +65,7 @@ pub struct Connection {
     visibility: Visibility,
 }
 
+#[allow(dead_code)]
 #[derive(Debug, Uclicious)]
 #[ucl(skip_builder)]
 pub struct Extra {
@@ -37,6 +37,23 @@ pub fn clone_trait() -> Path {
 pub fn into_trait() -> Path {
     syn::parse_str("::std::convert::Into").unwrap()
 }
+pub fn into_iterator_trait() -> Path {
+    syn::parse_str("::std::iter::IntoIterator").unwrap()
+}
+/// HashMap type.
+pub fn hash_map_ty() -> Path {
+    syn::parse_str("::std::collections::HashMap").unwrap()
+}
+/// Vec type.
+pub fn vec_ty() -> Path {
+    syn::parse_str("::std::vec::Vec").unwrap()
+}
+
+/// Arc type.
+pub fn arc_ty() -> Path {
+    syn::parse_str("::std::sync::Arc").unwrap()
+}
+
 pub fn box_ty() -> Path {
     syn::parse_str("::std::boxed::Box").unwrap()
 }
@@ -44,6 +61,10 @@ pub fn box_ty() -> Path {
 pub fn var_handler_trait() -> Path {
     syn::parse_str("::uclicious::traits::VariableHandler").unwrap()
 }
+
+pub fn macro_handler_trait() -> Path {
+    syn::parse_str("::uclicious::traits::MacroHandler").unwrap()
+}
 /// TryInto trait.
 pub fn try_into_trait() -> Path {
     syn::parse_str("::std::convert::TryInto").unwrap()
@@ -54,13 +75,26 @@ pub fn from_object_trait() -> Path {
     syn::parse_str("::uclicious::FromObject").unwrap()
 }
 
+/// BitOr trait.
+pub fn bit_or_trait() -> Path {
+    syn::parse_str("::std::ops::BitOr").unwrap()
+}
+
+/// Vec of strings.
+pub fn string_vec_ty() -> Type {
+    syn::parse_str("::std::vec::Vec<::std::string::String>").unwrap()
+}
+
 pub fn from_str_trait() -> Path {
     syn::parse_str("::std::str::FromStr").unwrap()
 }
 
 /// Boxed error type
 pub fn boxed_error() -> Type {
-    syn::parse_str("::std::boxed::Box<dyn ::std::error::Error>").unwrap()
+    syn::parse_str(
+        "::std::boxed::Box<dyn ::std::error::Error + ::std::marker::Send + ::std::marker::Sync>",
+    )
+    .unwrap()
 }
 
 pub fn deref_trait() -> Type {
@@ -71,11 +105,20 @@ pub fn borrow_trait() -> Type {
     syn::parse_str("::std::borrow::Borrow").unwrap()
 }
 
+pub fn to_owned_trait() -> Type {
+    syn::parse_str("::std::borrow::ToOwned").unwrap()
+}
+
 /// UCL Parser
 pub fn ucl_parser() -> Path {
     syn::parse_str("::uclicious::Parser").unwrap()
 }
 
+/// Where value of a field comes from.
+pub fn field_source_ty() -> Path {
+    syn::parse_str("::uclicious::raw::parser::FieldSource").unwrap()
+}
+
 /// UCL Parser
 pub fn ucl_parser_flags_ty() -> Path {
     syn::parse_str("::uclicious::ParserFlags").unwrap()
@@ -90,6 +133,16 @@ pub fn ucl_object_error() -> Path {
     syn::parse_str("::uclicious::ObjectError").unwrap()
 }
 
+/// Kind of the parser error.
+pub fn ucl_parser_error_type() -> Path {
+    syn::parse_str("::uclicious::UclErrorType").unwrap()
+}
+
+/// Lookup of an environment variable.
+pub fn env_var_fn() -> Path {
+    syn::parse_str("::std::env::var").unwrap()
+}
+
 /// UCL ObjectRef
 pub fn ucl_object_ref_ty() -> Path {
     syn::parse_str("::uclicious::ObjectRef").unwrap()
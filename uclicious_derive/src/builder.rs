@@ -1,6 +1,7 @@
 use crate::block::Block;
 use crate::initializer::Initializer;
 use crate::options::{Include, Parser, Variable};
+use crate::presence::FieldSources;
 use crate::{bindings, DEFAULT_STRUCT_NAME};
 use darling::ToTokens;
 use proc_macro2::{Span, TokenStream};
@@ -28,6 +29,12 @@ pub struct Builder<'a> {
     pub parser: &'a Parser,
     pub vars: Vec<Variable>,
     pub pre_source_hook: Option<Path>,
+    /// Whether to implement `Clone` by replaying recorded sources into a new builder.
+    pub clonable: bool,
+    /// Whether builder has a sink for deprecation notices.
+    pub deprecations: bool,
+    /// Whether builder keeps objects parsed from `env_path` includes to report fields they set.
+    pub track_presence: bool,
 }
 
 impl<'a> Builder<'a> {
@@ -67,17 +74,13 @@ pub struct BuildMethod<'a> {
     pub target_ty: &'a syn::Ident,
     /// Type parameters and lifetimes attached to this builder struct.
     pub target_ty_generics: Option<syn::TypeGenerics<'a>>,
-    /// Field initializers for the target type.
-    pub initializers: Vec<TokenStream>,
-    /// Doc-comment of the builder struct.
-    pub doc_comment: Option<syn::Attribute>,
-    /// Default value for the whole struct.
-    ///
-    /// This will be in scope for all initializers as `__default`.
-    pub default_struct: Option<Block>,
-    /// Validation function with signature `&FooBuilder -> Result<(), String>`
-    /// to call before the macro-provided struct buildout.
+    /// Validation function with signature `&Foo -> Result<(), E>`
+    /// to call on the built struct before returning it.
     pub validate_fn: Option<&'a syn::Path>,
+    /// Checks for deprecated keys to run before the struct buildout.
+    pub deprecations: Vec<TokenStream>,
+    /// Where fields come from, if the build method reports it.
+    pub sources: Option<FieldSources>,
 }
 
 impl<'a> FromObject<'a> {
@@ -113,6 +116,8 @@ pub struct FromObject<'a> {
     ///
     /// This will be in scope for all initializers as `__default`.
     pub default_struct: Option<Block>,
+    /// Whether initializers record where fields come from into `__sources`.
+    pub record_sources: bool,
 }
 
 impl<'a> ToTokens for FromObject<'a> {
@@ -120,6 +125,10 @@ impl<'a> ToTokens for FromObject<'a> {
         let target_ty = &self.target_ty;
         let target_ty_generics = &self.generics;
         let initializers = &self.initializers;
+        let default_struct = self.default_struct.as_ref().map(|default_expr| {
+            let ident = syn::Ident::new(DEFAULT_STRUCT_NAME, Span::call_site());
+            quote!(let #ident: #target_ty #target_ty_generics = #default_expr;)
+        });
 
         let result = bindings::result_ty();
         let error_ty = bindings::ucl_object_error();
@@ -128,12 +137,42 @@ impl<'a> ToTokens for FromObject<'a> {
         let obj_ty = bindings::ucl_object_ty();
         let borrow = bindings::borrow_trait();
 
+        let body = if self.record_sources {
+            let hash_map = bindings::hash_map_ty();
+            let field_source = bindings::field_source_ty();
+            let (impl_generics, ty_generics, where_clause) = self
+                .generics
+                .map(syn::Generics::split_for_impl)
+                .map(|(i, t, w)| (Some(i), Some(t), Some(w)))
+                .unwrap_or((None, None, None));
+            tokens.append_all(quote!(
+                impl #impl_generics #target_ty #ty_generics #where_clause {
+                    /// Same as `FromObject::try_from`, but records for every field whether its key is found or default is used.
+                    #[doc(hidden)]
+                    pub fn __try_from_with_sources(
+                        root: &#obj_ref_ty,
+                        __sources: &mut #hash_map<&'static str, #field_source>,
+                    ) -> #result<Self, #error_ty> {
+                        #default_struct
+                        Ok(#target_ty {
+                                #(#initializers)*
+                        })
+                    }
+                }
+            ));
+            quote!(Self::__try_from_with_sources(root, &mut #hash_map::new()))
+        } else {
+            quote!(
+                #default_struct
+                Ok(#target_ty {
+                    #(#initializers)*
+                })
+            )
+        };
         tokens.append_all(quote!(
             impl #try_from<&#obj_ref_ty> for #target_ty #target_ty_generics {
                 fn try_from(root: &#obj_ref_ty) -> #result<Self, #error_ty> {
-                    Ok(#target_ty {
-                            #(#initializers)*
-                    })
+                    #body
                 }
             }
             impl #try_from<#obj_ref_ty> for #target_ty #target_ty_generics {
@@ -156,21 +195,62 @@ impl<'a> ToTokens for BuildMethod<'a> {
         let vis = &self.visibility;
         let target_ty = &self.target_ty;
         let target_ty_generics = &self.target_ty_generics;
-        let default_struct = self.default_struct.as_ref().map(|default_expr| {
-            let ident = syn::Ident::new(DEFAULT_STRUCT_NAME, Span::call_site());
-            quote!(let #ident: #target_ty #target_ty_generics = #default_expr;)
-        });
         let result = bindings::result_ty();
         let boxed_error = bindings::boxed_error();
         let ucl_error_ty = bindings::ucl_parser_error();
         let ucl_obj_error_ty = bindings::ucl_object_error();
         let from_obj = bindings::from_object_trait();
+        let deprecations = &self.deprecations;
+        let validate = self
+            .validate_fn
+            .map(|validate_fn| quote!(#validate_fn(&target)?;));
+        let ref_ident = syn::Ident::new(&format!("{}_ref", ident), ident.span());
+        if let Some(ref sources) = self.sources {
+            let hash_map = bindings::hash_map_ty();
+            let field_source = bindings::field_source_ty();
+            let sources_ident = syn::Ident::new(&format!("{}_with_sources", ident), ident.span());
+            let inner_ident = syn::Ident::new(&format!("__{}_with_sources", ident), ident.span());
+            tokens.append_all(quote!(
+                #[doc = "Build target struct or return first encountered error."]
+                #vis fn #ident(mut self) -> #result<#target_ty #target_ty_generics, #boxed_error> {
+                    self.#ref_ident()
+                }
+
+                #[doc = "Build target struct without consuming the builder, so more sources can be added and it can be built again."]
+                #vis fn #ref_ident(&mut self) -> #result<#target_ty #target_ty_generics, #boxed_error> {
+                    self.#inner_ident().map(|(target, _)| target)
+                }
+
+                #[doc = "Build target struct and report for every field whether it was set by sources or got its default value."]
+                #vis fn #sources_ident(mut self) -> #result<(#target_ty #target_ty_generics, #hash_map<&'static str, #field_source>), #boxed_error> {
+                    self.#inner_ident()
+                }
+
+                fn #inner_ident(&mut self) -> #result<(#target_ty #target_ty_generics, #hash_map<&'static str, #field_source>), #boxed_error> {
+                    let root = self.__parser.get_object().map_err(|e: #ucl_error_ty| e.boxed() as #boxed_error)?;
+                    #(#deprecations)*
+                    let mut sources = #hash_map::new();
+                    let target = <#target_ty #target_ty_generics>::__try_from_with_sources(&root, &mut sources).map_err(|e: #ucl_obj_error_ty| e.boxed() as #boxed_error)?;
+                    #validate
+                    #sources
+                    Ok((target, sources))
+                }
+            ));
+            return;
+        }
         tokens.append_all(quote!(
             #[doc = "Build target struct or return first encountered error."]
             #vis fn #ident(mut self) -> #result<#target_ty #target_ty_generics, #boxed_error> {
-                #default_struct
+                self.#ref_ident()
+            }
+
+            #[doc = "Build target struct without consuming the builder, so more sources can be added and it can be built again."]
+            #vis fn #ref_ident(&mut self) -> #result<#target_ty #target_ty_generics, #boxed_error> {
                 let root = self.__parser.get_object().map_err(|e: #ucl_error_ty| e.boxed() as #boxed_error)?;
-                #from_obj::try_from(root).map_err(|e: #ucl_obj_error_ty| e.boxed() as #boxed_error)
+                #(#deprecations)*
+                let target: #target_ty #target_ty_generics = #from_obj::try_from(root).map_err(|e: #ucl_obj_error_ty| e.boxed() as #boxed_error)?;
+                #validate
+                Ok(target)
             }
         ))
     }
@@ -192,8 +272,11 @@ impl<'a> ToTokens for Builder<'a> {
             let traits: Punctuated<&Path, Token![,]> = Default::default();
             quote!(#traits)
         };
-        let includes: Vec<TokenStream> =
-            self.includes.iter().map(|e| e.to_token_stream()).collect();
+        let includes: Vec<TokenStream> = self
+            .includes
+            .iter()
+            .map(|e| e.tokens(self.track_presence))
+            .collect();
         let vars: Vec<TokenStream> = self.vars.iter().map(ToTokens::to_token_stream).collect();
         let builder_doc_comment = &self.doc_comment;
         let result_ty = bindings::result_ty();
@@ -206,6 +289,21 @@ impl<'a> ToTokens for Builder<'a> {
         } else {
             quote!()
         };
+        let parser_ty = bindings::ucl_parser();
+        let deprecation_init = if self.deprecations {
+            quote!(__deprecation_sink: None,)
+        } else {
+            quote!()
+        };
+        let (env_sources_decl, env_sources_init) = if self.track_presence {
+            let vec_ty = bindings::vec_ty();
+            (
+                quote!(let mut __env_sources = #vec_ty::new();),
+                quote!(__env_sources,),
+            )
+        } else {
+            (quote!(), quote!())
+        };
         tokens.append_all(quote!(
                 #[derive(#derived_traits)]
                 #builder_doc_comment
@@ -216,20 +314,81 @@ impl<'a> ToTokens for Builder<'a> {
                 #[allow(dead_code)]
                 impl #impl_generics #builder_ident #ty_generics #where_clause {
                     #(#functions)*
-                    /// Create a new builder.
-                    #builder_vis fn new() -> #result_ty<Self #ty_generics #where_clause, #ucl_error_ty> {
+                    /// Parser with flags, variables and macros from attributes, before any source is added.
+                    fn __new_parser() -> #result_ty<#parser_ty, #ucl_error_ty> {
                         #parser
                         #(#vars)*
                         #pre_source_hook
+                        Ok(parser)
+                    }
+                    /// Create a new builder.
+                    #builder_vis fn new() -> #result_ty<Self #ty_generics #where_clause, #ucl_error_ty> {
+                        let mut parser = Self::__new_parser()?;
+                        #env_sources_decl
                         #(#includes)*
                         Ok(
                             Self {
-                                __parser: parser
+                                __parser: parser,
+                                #deprecation_init
+                                #env_sources_init
                             }
                         )
                     }
                 }
             ));
+        if self.clonable {
+            let clone_trait = bindings::clone_trait();
+            let clone_deprecation = if self.deprecations {
+                quote!(__deprecation_sink: #clone_trait::clone(&self.__deprecation_sink),)
+            } else {
+                quote!()
+            };
+            // Values that `env_path` includes set are looked up again in the copy.
+            let clone_env_sources = if self.track_presence {
+                quote!(
+                    __env_sources: {
+                        let original = Self::__tracked_values(&self.__parser);
+                        let copied = Self::__tracked_values(&parser);
+                        self.__env_sources
+                            .iter()
+                            .filter(|(field, env)| {
+                                original
+                                    .iter()
+                                    .any(|(name, value)| name == field && value.as_ptr() == env.as_ptr())
+                            })
+                            .filter_map(|(field, _)| copied.iter().find(|(name, _)| name == field))
+                            .cloned()
+                            .collect()
+                    },
+                )
+            } else {
+                quote!()
+            };
+            tokens.append_all(quote!(
+                /// Creates a new builder with a deep copy of everything parsed by this one, so sources added to either of them don't affect the other.
+                ///
+                /// Parsed values are added to a fresh parser with `Parser::add_object`. Variables, variable handlers and macros added to this builder after it was created are not carried over.
+                ///
+                /// # Panics
+                ///
+                /// If `pre_source_hook` or `filevars` fail when the new parser is created.
+                impl #impl_generics #clone_trait for #builder_ident #ty_generics #where_clause {
+                    fn clone(&self) -> Self {
+                        let mut parser = Self::__new_parser().expect("Failed to create a parser for cloned builder");
+                        if let Ok(root) = self.__parser.get_object() {
+                            parser
+                                .add_object(&root)
+                                .expect("Failed to add a copy of parsed object to cloned builder");
+                        }
+                        Self {
+                            #clone_env_sources
+                            __parser: parser,
+                            #clone_deprecation
+                        }
+                    }
+                }
+            ));
+        }
     }
 }
 
@@ -240,6 +399,9 @@ impl<'a> ToTokens for IntoBuilder<'a> {
         let target = &self.target_ty;
         let result_ty = bindings::result_ty();
         let ucl_error_ty = bindings::ucl_parser_error();
+        let obj_ref_ty = bindings::ucl_object_ref_ty();
+        let obj_error_ty = bindings::ucl_object_error();
+        let from_obj = bindings::from_object_trait();
         let (_struct_generics, ty_generics, where_clause) = self
             .generics
             .map(syn::Generics::split_for_impl)
@@ -251,6 +413,11 @@ impl<'a> ToTokens for IntoBuilder<'a> {
                 #builder_vis fn builder() -> #result_ty<#builder_ident #ty_generics #where_clause, #ucl_error_ty> {
                     #builder_ident::new()
                 }
+
+                /// Creates this struct from an already parsed object, skipping the parser.
+                #builder_vis fn from_object(obj: &#obj_ref_ty) -> #result_ty<Self, #obj_error_ty> {
+                    #from_obj::try_from(obj)
+                }
             }
         ));
     }
@@ -0,0 +1,64 @@
+use crate::bindings;
+use darling::ToTokens;
+use proc_macro2::{Ident, TokenStream};
+use quote::TokenStreamExt;
+
+/// Builder field holding a sink for deprecation notices.
+pub struct DeprecationField;
+
+impl ToTokens for DeprecationField {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let ident: Ident = syn::parse_str("__deprecation_sink").unwrap();
+        let option_ty = bindings::option_ty();
+        let arc_ty = bindings::arc_ty();
+        tokens.append_all(quote!(
+            #ident: #option_ty<#arc_ty<dyn Fn(&str, &str)>>,
+        ));
+    }
+}
+
+#[derive(Debug)]
+pub struct DeprecationMethods {
+    /// Visibility of the build method, e.g. `syn::Visibility::Public`.
+    pub visibility: syn::Visibility,
+}
+
+impl ToTokens for DeprecationMethods {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let vis = &self.visibility;
+        let box_ty = bindings::box_ty();
+        let into_trait = bindings::into_trait();
+        tokens.append_all(quote!(
+            /// Set a function to call with path and message when a deprecated key is found during build.
+            ///
+            /// By default notices are printed to stderr.
+            #vis fn set_deprecation_sink(&mut self, sink: #box_ty<dyn Fn(&str, &str)>) -> &mut Self {
+                self.__deprecation_sink = Some(#into_trait::into(sink));
+                self
+            }
+        ))
+    }
+}
+
+/// Statement that reports a deprecated key if it's present in `root`.
+pub struct DeprecationCheck<'a> {
+    pub lookup_path: &'a str,
+    pub path_sep: char,
+    pub message: &'a str,
+}
+
+impl<'a> ToTokens for DeprecationCheck<'a> {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let lookup_path = self.lookup_path;
+        let path_sep = self.path_sep;
+        let message = self.message;
+        tokens.append_all(quote!(
+            if root.lookup_path_sep(#lookup_path, #path_sep).is_some() {
+                match self.__deprecation_sink {
+                    Some(ref sink) => sink(#lookup_path, #message),
+                    None => eprintln!("`{}` is deprecated: {}", #lookup_path, #message),
+                }
+            }
+        ))
+    }
+}
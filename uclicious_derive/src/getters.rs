@@ -0,0 +1,34 @@
+use darling::ToTokens;
+use proc_macro2::TokenStream;
+use quote::TokenStreamExt;
+
+/// Getters on the target struct, one per field.
+#[derive(Debug)]
+pub struct Getters<'a> {
+    /// Type of the target.
+    pub target_ty: &'a syn::Ident,
+    /// Type parameters and lifetimes attached to the target type.
+    pub generics: &'a syn::Generics,
+    /// Name and type of every field.
+    pub fields: Vec<(&'a syn::Ident, &'a syn::Type)>,
+}
+
+impl<'a> ToTokens for Getters<'a> {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let target_ty = self.target_ty;
+        let (impl_generics, ty_generics, where_clause) = self.generics.split_for_impl();
+        let getters = self.fields.iter().map(|(ident, ty)| {
+            quote!(
+                #[allow(dead_code)]
+                pub fn #ident(&self) -> &#ty {
+                    &self.#ident
+                }
+            )
+        });
+        tokens.append_all(quote!(
+            impl #impl_generics #target_ty #ty_generics #where_clause {
+                #(#getters)*
+            }
+        ));
+    }
+}
@@ -4,6 +4,14 @@ use proc_macro2::{Span, TokenStream};
 use quote::{ToTokens, TokenStreamExt};
 use syn::Path;
 
+#[derive(Debug, Clone)]
+pub enum Flatten {
+    /// Collect keys that are not used by named fields into a map.
+    Map(Vec<String>),
+    /// Read field from the same object as its parent.
+    Object,
+}
+
 #[derive(Debug, Clone)]
 pub struct Initializer<'a> {
     /// Name of the target field.
@@ -12,41 +20,91 @@ pub struct Initializer<'a> {
     ///
     /// This takes precedence over a default struct identifier.
     pub default_value: Option<Block>,
+    /// Fallible function to call for the default value.
+    pub default_fn: Option<&'a Path>,
     /// Whether the build_method defines a default struct.
     pub use_default_struct: bool,
     /// path that will be passed down to lookup method.
     pub lookup_path: String,
+    /// Separator of segments in `lookup_path`.
+    pub path_sep: char,
     pub validation: Option<Path>,
     pub from: Option<Path>,
     pub try_from: Option<Path>,
     pub map: Option<Path>,
     pub from_str: bool,
+    pub parse_with: Option<Path>,
+    pub flags_from: Option<Path>,
+    pub split: Option<String>,
+    /// Only set for flattened fields.
+    pub flatten: Option<Flatten>,
+    /// Use default value without looking up the key.
+    pub skip: bool,
+    /// Record in `__sources` whether the key was found or the default was used.
+    pub record_source: bool,
 }
 
 impl<'a> ToTokens for Initializer<'a> {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         let struct_field = &self.field_ident;
+        if self.skip {
+            let default_value = self.skipped_value();
+            tokens.append_all(quote!(
+                #struct_field: #default_value,
+            ));
+            return;
+        }
+        if let Some(ref flatten) = self.flatten {
+            let flatten = self.match_flatten(flatten);
+            tokens.append_all(quote!(
+                #struct_field: #flatten,
+            ));
+            return;
+        }
         let lookup_path = &self.lookup_path;
+        let lookup_path_bytes =
+            syn::LitByteStr::new(format!("{}\0", lookup_path).as_bytes(), Span::call_site());
+        let path_sep = self.path_sep as u8;
         let match_none = self.match_none();
         let match_some = self.match_some();
+        let (record_config, record_default) = self.record_source();
         tokens.append_all(quote!(
-            #struct_field: match root.lookup_path(#lookup_path) {
+            #struct_field: match root.lookup_path_bytes_sep(#lookup_path_bytes, #path_sep) {
                 Some(obj) => {
+                    #record_config
                     let lookup_path = #lookup_path;
                     #match_some
                 },
-                #match_none
+                None => {
+                    #record_default
+                    #match_none
+                }
             },
         ));
     }
 }
 
 impl<'a> Initializer<'a> {
+    /// Statements that record `FieldSource::Config` and `FieldSource::Default` respectively, if sources are recorded.
+    fn record_source(&self) -> (TokenStream, TokenStream) {
+        if !self.record_source {
+            return (quote!(), quote!());
+        }
+        let field_source = bindings::field_source_ty();
+        let name = self.field_ident.to_string();
+        let name = name.trim_start_matches("r#");
+        (
+            quote!(__sources.insert(#name, #field_source::Config);),
+            quote!(__sources.insert(#name, #field_source::Default);),
+        )
+    }
+
     /// To be used inside of `#struct_field: match self.#builder_field { ... }`
     fn match_none(&'a self) -> MatchNone<'a> {
-        match self.default_value {
-            Some(ref expr) => MatchNone::DefaultTo(expr),
-            None => {
+        match (&self.default_value, self.default_fn) {
+            (Some(ref expr), _) => MatchNone::DefaultTo(expr),
+            (None, Some(default_fn)) => MatchNone::CallDefault(default_fn),
+            (None, None) => {
                 if self.use_default_struct {
                     MatchNone::UseDefaultStructField(self.field_ident)
                 } else {
@@ -55,42 +113,124 @@ impl<'a> Initializer<'a> {
             }
         }
     }
-    fn match_some(&'a self) -> MatchSome {
+    fn match_some(&'a self) -> MatchSome<'a> {
         match (
             &self.validation,
             &self.from,
             &self.try_from,
             &self.map,
             &self.from_str,
+            &self.flags_from,
+            &self.split,
+            &self.parse_with,
         ) {
-            (None, None, None, None, false) => MatchSome::Simple,
-            (Some(validation), None, None, None, false) => MatchSome::Validation(validation),
-            (None, Some(src_type), None, None, false) => MatchSome::From(src_type),
-            (None, None, Some(src_type), None, false) => MatchSome::TryFrom(src_type),
-            (Some(validation), Some(from), None, None, false) => {
+            (None, None, None, None, false, None, None, None) => MatchSome::Simple,
+            (Some(validation), None, None, None, false, None, None, None) => MatchSome::Validation(validation),
+            (None, Some(src_type), None, None, false, None, None, None) => MatchSome::From(src_type),
+            (None, None, Some(src_type), None, false, None, None, None) => MatchSome::TryFrom(src_type),
+            (Some(validation), Some(from), None, None, false, None, None, None) => {
                 MatchSome::FromValidation(from, validation)
             }
-            (Some(validation), None, Some(from), None, false) => {
+            (Some(validation), None, Some(from), None, false, None, None, None) => {
                 MatchSome::TryFromValidation(from, validation)
             }
-            (None, None, None, Some(map_func), false) => MatchSome::Map(map_func),
-            (Some(validation), None, None, Some(map_func), false) => {
+            (None, None, None, Some(map_func), false, None, None, None) => MatchSome::Map(map_func),
+            (Some(validation), None, None, Some(map_func), false, None, None, None) => {
                 MatchSome::MapValidation(map_func, validation)
             }
-            (None, None, None, None, true) => MatchSome::FromStr,
-            (Some(validation), None, None, None, true) => MatchSome::FromStrValidation(validation),
+            (None, None, None, None, true, None, None, None) => MatchSome::FromStr,
+            (Some(validation), None, None, None, true, None, None, None) => {
+                MatchSome::FromStrValidation(validation)
+            }
+            (None, None, None, None, false, Some(flag_func), None, None) => MatchSome::Flags(flag_func),
+            (Some(validation), None, None, None, false, Some(flag_func), None, None) => {
+                MatchSome::FlagsValidation(flag_func, validation)
+            }
+            (None, None, None, None, false, None, Some(delimiter), None) => MatchSome::Split(delimiter),
+            (Some(validation), None, None, None, false, None, Some(delimiter), None) => {
+                MatchSome::SplitValidation(delimiter, validation)
+            }
+            (None, None, None, None, false, None, None, Some(parse_func)) => MatchSome::ParseWith(parse_func),
+            (Some(validation), None, None, None, false, None, None, Some(parse_func)) => {
+                MatchSome::ParseWithValidation(parse_func, validation)
+            }
             _ => panic!(
-                "field {}: map, from and try_from are mutually exclusive",
+                "field {}: map, from, try_from, from_str, parse_with, flags_from and split are mutually exclusive",
                 self.field_ident
             ),
         }
     }
 }
 
+impl<'a> Initializer<'a> {
+    /// Value for a skipped field: explicit default, then default struct, then `Default::default()`.
+    fn skipped_value(&self) -> TokenStream {
+        match (&self.default_value, self.default_fn) {
+            (Some(ref expr), _) => quote!(#expr),
+            (None, Some(default_fn)) => quote!(#default_fn()?),
+            (None, None) if self.use_default_struct => {
+                let struct_ident = syn::Ident::new(DEFAULT_STRUCT_NAME, Span::call_site());
+                let field_ident = self.field_ident;
+                quote!(#struct_ident.#field_ident)
+            }
+            (None, None) => {
+                let default_trait = bindings::default_trait();
+                quote!(#default_trait::default())
+            }
+        }
+    }
+
+    /// Read a flattened field from the same object as its parent.
+    fn match_flatten(&self, flatten: &Flatten) -> TokenStream {
+        let from_object = bindings::from_object_trait();
+        let result_ty = bindings::result_ty();
+        let object_error_ty = bindings::ucl_object_error();
+        let lookup_path = &self.lookup_path;
+        let validation = match self.match_some() {
+            MatchSome::Simple => quote!(v),
+            MatchSome::Validation(path) => quote!(#path(&lookup_path, &v)
+                .map(|_| v)
+                .map_err(#object_error_ty::from_validation)?),
+            _ => panic!(
+                "field {}: flatten can only be combined with validate",
+                self.field_ident
+            ),
+        };
+        let used_keys = match flatten {
+            Flatten::Map(used_keys) => used_keys,
+            Flatten::Object => {
+                return quote!({
+                    let lookup_path = #lookup_path;
+                    let v = #from_object::try_from(root)?;
+                    #validation
+                });
+            }
+        };
+        quote!({
+            let lookup_path = #lookup_path;
+            let used_keys: &[&str] = &[#(#used_keys),*];
+            let v = root
+                .iter()
+                .filter_map(|obj| {
+                    let key = obj.key()?;
+                    if used_keys.contains(&key.as_str()) {
+                        None
+                    } else {
+                        Some(#from_object::try_from(obj).map(|v| (key, v)))
+                    }
+                })
+                .collect::<#result_ty<_, #object_error_ty>>()?;
+            #validation
+        })
+    }
+}
+
 // To be used inside of `#struct_field: match self.#builder_field { ... }`
 enum MatchNone<'a> {
     /// Inner value must be a valid Rust expression
     DefaultTo(&'a Block),
+    /// Inner value must be a function returning `Result<T, ObjectError>`
+    CallDefault(&'a Path),
     /// Inner value must be the field identifier
     ///
     /// The default struct must be in scope in the build_method.
@@ -110,6 +250,12 @@ enum MatchSome<'a> {
     MapValidation(&'a Path, &'a Path),
     FromStr,
     FromStrValidation(&'a Path),
+    ParseWith(&'a Path),
+    ParseWithValidation(&'a Path, &'a Path),
+    Flags(&'a Path),
+    FlagsValidation(&'a Path, &'a Path),
+    Split(&'a str),
+    SplitValidation(&'a str, &'a Path),
 }
 
 impl<'a> ToTokens for MatchNone<'a> {
@@ -117,16 +263,19 @@ impl<'a> ToTokens for MatchNone<'a> {
         let obj_error_ty = bindings::ucl_object_error();
         match *self {
             MatchNone::DefaultTo(expr) => tokens.append_all(quote!(
-                None => #expr
+                #expr
+            )),
+            MatchNone::CallDefault(default_fn) => tokens.append_all(quote!(
+                #default_fn()?
             )),
             MatchNone::UseDefaultStructField(field_ident) => {
                 let struct_ident = syn::Ident::new(DEFAULT_STRUCT_NAME, Span::call_site());
                 tokens.append_all(quote!(
-                    None => #struct_ident.#field_ident
+                    #struct_ident.#field_ident
                 ))
             }
             MatchNone::ReturnError(ref err) => tokens.append_all(quote!(
-                None => return ::std::result::Result::Err(#obj_error_ty::KeyNotFound(#err.to_string()))
+                return ::std::result::Result::Err(#obj_error_ty::KeyNotFound(#err.to_string()))
             )),
         }
     }
@@ -140,11 +289,40 @@ impl<'a> ToTokens for MatchSome<'a> {
         let object_error_ty = bindings::ucl_object_error();
         let string_ty = bindings::string_ty();
         let from_str_trait = bindings::from_str_trait();
+        let default_trait = bindings::default_trait();
+        let bit_or_trait = bindings::bit_or_trait();
+        let string_vec_ty = bindings::string_vec_ty();
+        let fold_flags = |flag_func: &Path| {
+            quote!(
+                let names: #string_vec_ty = #from_object::try_from(obj)?;
+                let mut v = #default_trait::default();
+                for name in names.iter() {
+                    let flag = #flag_func(name)
+                        .ok_or_else(|| #object_error_ty::other(format!("unknown flag `{}`", name)))?;
+                    v = #bit_or_trait::bitor(v, flag);
+                }
+            )
+        };
+        let split = |delimiter: &str| {
+            quote!(
+                let v = match obj.as_string() {
+                    Some(s) => s
+                        .split(#delimiter)
+                        .map(str::trim)
+                        .filter(|part| !part.is_empty())
+                        .map(#string_ty::from)
+                        .collect(),
+                    None => #from_object::try_from(obj)?,
+                };
+            )
+        };
         let quote = match self {
             MatchSome::Simple => quote!(#from_object::try_from(obj)?),
             MatchSome::Validation(path) => quote!(
                 let v = #from_object::try_from(obj)?;
-                #path(&lookup_path, &v).map(|_| v)?
+                #path(&lookup_path, &v)
+                    .map(|_| v)
+                    .map_err(#object_error_ty::from_validation)?
             ),
             MatchSome::From(src_type) => quote!(
                 let v: #src_type = #from_object::try_from(obj)?;
@@ -158,20 +336,26 @@ impl<'a> ToTokens for MatchSome<'a> {
             MatchSome::FromValidation(src_type, validation) => quote!(
                  let v: #src_type = #from_object::try_from(obj)?;
                  let v = #into_trait::into(v);
-                 #validation(&lookup_path, &v).map(|_| v)?
+                 #validation(&lookup_path, &v)
+                    .map(|_| v)
+                    .map_err(#object_error_ty::from_validation)?
             ),
             MatchSome::TryFromValidation(src_type, validation) => quote!(
                 let v: #src_type = #from_object::try_from(obj)?;
                 let v = #try_into_trait::try_into(v)
                         .map_err(|e| #object_error_ty::other(e))?;
-                #validation(&lookup_path, &v).map(|_| v)?
+                #validation(&lookup_path, &v)
+                    .map(|_| v)
+                    .map_err(#object_error_ty::from_validation)?
             ),
             MatchSome::Map(map_func) => quote!(
                 #map_func(obj)?
             ),
             MatchSome::MapValidation(map_func, validation) => quote!(
                 let v = #map_func(obj)?;
-                #validation(&lookup_path, &v).map(|_| v)?
+                #validation(&lookup_path, &v)
+                    .map(|_| v)
+                    .map_err(#object_error_ty::from_validation)?
             ),
             MatchSome::FromStr => quote!(
                 let v: #string_ty = #from_object::try_from(obj)?;
@@ -182,8 +366,55 @@ impl<'a> ToTokens for MatchSome<'a> {
                 let v: #string_ty = #from_object::try_from(obj)?;
                 let v = #from_str_trait::from_str(&v)
                         .map_err(|e| #object_error_ty::other(e))?;
-                #validation(&lookup_path, &v).map(|_| v)?
+                #validation(&lookup_path, &v)
+                    .map(|_| v)
+                    .map_err(#object_error_ty::from_validation)?
+            ),
+            MatchSome::ParseWith(parse_func) => quote!(
+                let v: #string_ty = #from_object::try_from(obj)?;
+                #parse_func(&v)
+                        .map_err(|e| #object_error_ty::other(e))?
             ),
+            MatchSome::ParseWithValidation(parse_func, validation) => quote!(
+                let v: #string_ty = #from_object::try_from(obj)?;
+                let v = #parse_func(&v)
+                        .map_err(|e| #object_error_ty::other(e))?;
+                #validation(&lookup_path, &v)
+                    .map(|_| v)
+                    .map_err(#object_error_ty::from_validation)?
+            ),
+            MatchSome::Flags(flag_func) => {
+                let fold = fold_flags(flag_func);
+                quote!(
+                    #fold
+                    v
+                )
+            }
+            MatchSome::FlagsValidation(flag_func, validation) => {
+                let fold = fold_flags(flag_func);
+                quote!(
+                    #fold
+                    #validation(&lookup_path, &v)
+                    .map(|_| v)
+                    .map_err(#object_error_ty::from_validation)?
+                )
+            }
+            MatchSome::Split(delimiter) => {
+                let split = split(delimiter);
+                quote!(
+                    #split
+                    v
+                )
+            }
+            MatchSome::SplitValidation(delimiter, validation) => {
+                let split = split(delimiter);
+                quote!(
+                    #split
+                    #validation(&lookup_path, &v)
+                    .map(|_| v)
+                    .map_err(#object_error_ty::from_validation)?
+                )
+            }
         };
         tokens.append_all(quote);
     }
@@ -14,13 +14,18 @@ use darling::FromDeriveInput;
 use syn::{parse_macro_input, DeriveInput};
 
 mod options;
-use options::Options;
+use options::{EnumOptions, Options};
 
 mod bindings;
 mod block;
 mod builder;
+mod deprecation;
+mod getters;
 mod initializer;
 mod parser;
+mod presence;
+mod setter;
+mod variant;
 
 const DEFAULT_STRUCT_NAME: &str = "__default";
 
@@ -28,7 +33,21 @@ const DEFAULT_STRUCT_NAME: &str = "__default";
 pub fn derive(input: TokenStream) -> TokenStream {
     let ast = parse_macro_input!(input as DeriveInput);
 
-    derive_for_struct(ast).into()
+    match ast.data {
+        syn::Data::Enum(_) => derive_for_enum(ast).into(),
+        _ => derive_for_struct(ast).into(),
+    }
+}
+
+fn derive_for_enum(ast: syn::DeriveInput) -> proc_macro2::TokenStream {
+    let opts: EnumOptions = match EnumOptions::from_derive_input(&ast) {
+        Ok(val) => val,
+        Err(err) => {
+            return err.write_errors();
+        }
+    };
+    let from_object = opts.as_from_object();
+    quote!(#from_object)
 }
 
 #[allow(clippy::let_and_return)]
@@ -47,12 +66,25 @@ fn derive_for_struct(ast: syn::DeriveInput) -> proc_macro2::TokenStream {
 
     builder.push_field(&parser::ParserField::default());
     builder.push_method(&opts.as_parser_methods());
+    if opts.has_deprecations() {
+        builder.push_field(&deprecation::DeprecationField);
+        builder.push_method(&opts.as_deprecation_methods());
+    }
     for field in opts.fields() {
         from_object.push_initializer(field.as_initializer());
+        if opts.programmatic_setters() {
+            if let Some(setter) = field.as_setter() {
+                builder.push_method(&setter);
+            }
+        }
+    }
+    if opts.track_presence() {
+        builder.push_field(&presence::EnvSourcesField);
+        builder.push_method(&presence::TrackedValuesMethod(opts.as_field_sources()));
     }
     builder.push_method(&build_fn);
 
-    let tokens = if opts.skip_builder() {
+    let mut tokens = if opts.skip_builder() {
         quote!(#from_object)
     } else {
         quote!(
@@ -61,6 +93,10 @@ fn derive_for_struct(ast: syn::DeriveInput) -> proc_macro2::TokenStream {
             #builder
         )
     };
+    if opts.getters() {
+        let getters = opts.as_getters();
+        tokens.extend(quote!(#getters));
+    }
     //panic!(tokens.to_string());
     tokens
 }
@@ -1,14 +1,19 @@
 use crate::bindings;
 use crate::block::Block;
 use crate::builder::{BuildMethod, Builder, FromObject, IntoBuilder};
-use crate::initializer::Initializer;
+use crate::deprecation::{DeprecationCheck, DeprecationMethods};
+use crate::getters::Getters;
+use crate::initializer::{Flatten, Initializer};
 use crate::parser::ParserMethods;
-use darling::util::{Flag, PathList};
+use crate::presence::FieldSources;
+use crate::setter::Setter;
+use crate::variant::{EnumFromObject, VariantMatch};
+use darling::util::Flag;
 use darling::{self, ToTokens};
 use proc_macro2::{Span, TokenStream};
 use quote::TokenStreamExt;
 use std::vec::IntoIter;
-use syn::{Attribute, Generics, Ident, Path, Visibility};
+use syn::{Generics, Ident, Path, Visibility};
 
 #[derive(Debug, Clone, FromMeta)]
 pub struct Variable {
@@ -31,11 +36,81 @@ pub struct FileVars {
     expand: Option<bool>,
 }
 
+/// Macro handler to register on a parser.
+#[derive(Debug, Clone, FromMeta)]
+pub struct Macro {
+    name: String,
+    /// Function that returns a macro handler.
+    handler: Path,
+}
+
+impl ToTokens for Macro {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let name = &self.name;
+        let handler = &self.handler;
+        let box_ty = bindings::box_ty();
+        tokens.append_all(quote!(
+            parser.register_macro(#name, #box_ty::new(#handler()));
+        ));
+    }
+}
+
+/// Flags of the inner parser.
+#[derive(Debug, Clone)]
+pub enum ParserFlags {
+    /// Path to a function that returns flags, e.g. `flags = "path::to_fn"`.
+    Fn(Path),
+    /// Flags named inline, e.g. `flags(key_lowercase, no_time)`.
+    List(Vec<Ident>),
+}
+
+impl ParserFlags {
+    /// Libucl flags that can be named inline.
+    const KNOWN: &'static [&'static str] = &[
+        "key_lowercase",
+        "no_time",
+        "no_implicit_arrays",
+        "save_comments",
+        "disable_macro",
+        "no_filevars",
+    ];
+}
+
+impl darling::FromMeta for ParserFlags {
+    fn from_string(value: &str) -> darling::Result<Self> {
+        Path::from_string(value).map(ParserFlags::Fn)
+    }
+
+    fn from_list(items: &[syn::NestedMeta]) -> darling::Result<Self> {
+        let mut flags = Vec::with_capacity(items.len());
+        for item in items {
+            match item {
+                syn::NestedMeta::Meta(syn::Meta::Path(path)) => match path.get_ident() {
+                    Some(ident) if Self::KNOWN.contains(&ident.to_string().as_str()) => {
+                        flags.push(ident.clone())
+                    }
+                    Some(ident) => {
+                        return Err(
+                            darling::Error::unknown_value(&ident.to_string()).with_span(ident)
+                        )
+                    }
+                    None => return Err(darling::Error::unsupported_format("path").with_span(path)),
+                },
+                other => return Err(darling::Error::unsupported_format("literal").with_span(other)),
+            }
+        }
+        Ok(ParserFlags::List(flags))
+    }
+}
+
 #[derive(Debug, Clone, FromMeta, Default)]
 pub struct Parser {
     #[darling(default)]
-    flags: Option<Path>,
+    flags: Option<ParserFlags>,
+    #[darling(default)]
     filevars: Option<FileVars>,
+    #[darling(default, multiple)]
+    register_macro: Vec<Macro>,
 }
 
 impl ToTokens for Parser {
@@ -43,8 +118,17 @@ impl ToTokens for Parser {
         let parser_ty = bindings::ucl_parser();
         let parser_flags_ty = bindings::ucl_parser_flags_ty();
         if let Some(ref flags) = self.flags {
+            let flags = match flags {
+                ParserFlags::Fn(flags) => quote!(#flags()),
+                ParserFlags::List(list) => {
+                    let consts = list
+                        .iter()
+                        .map(|flag| Ident::new(&flag.to_string().to_uppercase(), flag.span()));
+                    quote!(#parser_flags_ty::DEFAULT #(| #parser_flags_ty::#consts)*)
+                }
+            };
             tokens.append_all(quote!(
-                let flags: #parser_flags_ty = #flags();
+                let flags: #parser_flags_ty = #flags;
                 let mut parser = #parser_ty::with_flags(flags);
             ));
         } else {
@@ -60,6 +144,9 @@ impl ToTokens for Parser {
                 let _ = parser.set_filevars(#path, #expand)?;
             ));
         }
+        for register_macro in self.register_macro.iter() {
+            register_macro.to_tokens(tokens);
+        }
     }
 }
 #[derive(Debug, Clone, FromMeta)]
@@ -71,38 +158,78 @@ pub struct Include {
     #[darling(default)]
     chunk_static: Option<String>,
     #[darling(default)]
+    env_path: Option<String>,
+    #[darling(default)]
+    suffix: Option<String>,
+    #[darling(default)]
     priority: Option<u32>,
     #[darling(default)]
     strategy: Option<Path>,
 }
 
-impl ToTokens for Include {
-    fn to_tokens(&self, tokens: &mut TokenStream) {
+impl Include {
+    /// Statements that add this include to `parser`.
+    ///
+    /// With `record_env` values that files included with `env_path` set for fields are pushed to `__env_sources`.
+    pub fn tokens(&self, record_env: bool) -> TokenStream {
+        let mut tokens = TokenStream::new();
         let priority = self.priority.unwrap_or(0);
         let strategy = match self.strategy {
             Some(ref s) => s.clone(),
             None => bindings::ucl_default_strategy(),
         };
         let into_trait = bindings::into_trait();
+        let add_file = |path: TokenStream| quote!(parser.add_file_full(#path, #into_trait::into(#priority), #strategy)?;);
+        let add_chunk = |chunk: TokenStream| quote!(parser.add_chunk_full(#chunk, #into_trait::into(#priority), #strategy)?;);
 
-        match (&self.path, &self.chunk, &self.chunk_static) {
-            (Some(path), None, None) => {
-                tokens.append_all(quote!(
-                    parser.add_file_full(#path, #into_trait::into(#priority), #strategy)?;
-                ));
+        if self.suffix.is_some() && self.env_path.is_none() {
+            panic!("`suffix` can only be used together with `env_path`");
+        }
+
+        match (&self.path, &self.chunk, &self.chunk_static, &self.env_path) {
+            (Some(path), None, None, None) => {
+                tokens.append_all(add_file(quote!(#path)));
             }
-            (None, Some(chunk), None) => {
-                tokens.append_all(quote!(
-                    parser.add_chunk_full(#chunk, #into_trait::into(#priority), #strategy)?;
-                ));
+            (None, Some(chunk), None, None) => {
+                tokens.append_all(add_chunk(quote!(#chunk)));
+            }
+            (None, None, Some(path), None) => {
+                tokens.append_all(add_chunk(quote!(::std::include_str!(#path))));
             }
-            (None, None, Some(path)) => {
+            (None, None, None, Some(env)) => {
+                let suffix = self.suffix.clone().unwrap_or_default();
+                let env_var = bindings::env_var_fn();
+                let err = bindings::ucl_parser_error();
+                let err_kind = bindings::ucl_parser_error_type();
+                let add = add_file(quote!(&path));
+                let add = if record_env {
+                    quote!(
+                        let before = Self::__tracked_values(&parser);
+                        #add
+                        for (field, value) in Self::__tracked_values(&parser) {
+                            if !before.iter().any(|(_, old)| old.as_ptr() == value.as_ptr()) {
+                                __env_sources.push((field, value));
+                            }
+                        }
+                    )
+                } else {
+                    add
+                };
                 tokens.append_all(quote!(
-                    parser.add_chunk_full(include_str!(#path), #into_trait::into(#priority), #strategy)?;
+                    {
+                        let prefix = #env_var(#env).map_err(|e| {
+                            #err::new(#err_kind::Io, format!("Can't resolve include path from `{}`: {}", #env, e))
+                        })?;
+                        let path = format!("{}{}", prefix, #suffix);
+                        #add
+                    }
                 ));
             }
-            (_, _, _) => panic!("Unsupported include combination!"),
+            (_, _, _, _) => panic!(
+                "`include` must specify exactly one of `path`, `chunk`, `chunk_static` or `env_path`"
+            ),
         }
+        tokens
     }
 }
 
@@ -144,14 +271,9 @@ impl FlagVisibility for FieldMeta {
 }
 
 #[derive(Debug, Clone, FromDeriveInput)]
-#[darling(
-    attributes(ucl),
-    forward_attrs(doc, cfg, allow),
-    supports(struct_named)
-)]
+#[darling(attributes(ucl), supports(struct_named))]
 pub struct Options {
     ident: Ident,
-    attrs: Vec<Attribute>,
     vis: Visibility,
     generics: Generics,
     /// The name of the generated builder. Defaults to `#{ident}Builder`.
@@ -161,10 +283,6 @@ pub struct Options {
     #[darling(default)]
     build_fn: BuildFn,
 
-    /// Additional traits to derive on the builder.
-    #[darling(default)]
-    derive: PathList,
-
     /// Struct-level value to use in place of any unfilled fields
     #[darling(default)]
     default: Option<DefaultExpression>,
@@ -178,6 +296,22 @@ pub struct Options {
     #[darling(default)]
     skip_builder: bool,
 
+    /// Implement `Clone` for the builder.
+    #[darling(default)]
+    clonable_builder: bool,
+
+    /// Generate `with_*` setters on the builder.
+    #[darling(default)]
+    programmatic_setters: bool,
+
+    /// Generate `field_sources` on the builder.
+    #[darling(default)]
+    track_presence: bool,
+
+    /// Generate getters on the target struct.
+    #[darling(default)]
+    getters: bool,
+
     /// The parsed body of the derived struct.
     data: darling::ast::Data<darling::util::Ignored, Field>,
 
@@ -194,15 +328,21 @@ pub struct Options {
 
     #[darling(default)]
     pre_source_hook: Option<Path>,
+
+    /// Rule for fields without explicit `path`. Field names are used as is by default.
+    #[darling(default)]
+    rename_all: Option<RenameRule>,
+
+    /// Separator of segments in field paths, `.` by default.
+    #[darling(default)]
+    path_sep: Option<String>,
 }
 
 /// Data extracted from the fields of the input struct.
 #[derive(Debug, Clone, FromField)]
-#[darling(attributes(ucl), forward_attrs(doc, cfg, allow))]
+#[darling(attributes(ucl))]
 pub struct Field {
     ident: Option<Ident>,
-    attrs: Vec<Attribute>,
-    vis: syn::Visibility,
     ty: syn::Type,
     #[darling(default)]
     public: Flag,
@@ -210,6 +350,9 @@ pub struct Field {
     private: Flag,
     #[darling(default)]
     default: Option<DefaultExpression>,
+    /// Function to call for a value if key not found, returns `Result<T, ObjectError>`.
+    #[darling(default)]
+    default_fn: Option<Path>,
     #[darling(default)]
     path: Option<String>,
     #[darling(default)]
@@ -222,6 +365,33 @@ pub struct Field {
     map: Option<Path>,
     #[darling(default)]
     from_str: bool,
+    /// Function that parses a string value, used instead of `FromStr`.
+    #[darling(default)]
+    parse_with: Option<Path>,
+    /// Function that maps a single string to a flag, flags from an array are combined with `|`.
+    #[darling(default)]
+    flags_from: Option<Path>,
+    /// Delimiter to split a single string value by, arrays are read as usual.
+    #[darling(default)]
+    split: Option<String>,
+    /// Capture all keys at this level that are not used by other fields.
+    #[darling(default)]
+    flatten: bool,
+    /// Message to report when this key is present.
+    #[darling(default)]
+    deprecated: Option<String>,
+    /// Don't look up this field, always use default value.
+    #[darling(default)]
+    skip: bool,
+    /// Capture all keys not used by other fields, regardless of field type.
+    #[darling(default)]
+    rest: bool,
+    /// Don't generate a `with_*` setter for this field.
+    #[darling(default)]
+    skip_setter: bool,
+    /// Same as `from = "f64"`.
+    #[darling(default)]
+    float_from_int: bool,
 }
 impl FlagVisibility for Field {
     fn public(&self) -> &Flag {
@@ -234,10 +404,13 @@ impl FlagVisibility for Field {
 }
 
 impl Field {
-    fn get_lookup_key(&self) -> String {
+    fn get_lookup_key(&self, rename_all: Option<RenameRule>) -> String {
         match (&self.ident, &self.path) {
             (_, Some(path)) => path.clone(),
-            (Some(ident), None) => ident.clone().to_string(),
+            (Some(ident), None) => match rename_all {
+                Some(rule) => rule.apply_to_field(&ident.to_string()),
+                None => ident.to_string(),
+            },
             (_, _) => panic!("Can't figure out key path"),
         }
     }
@@ -338,6 +511,27 @@ impl Options {
     pub fn skip_builder(&self) -> bool {
         self.skip_builder
     }
+    pub fn programmatic_setters(&self) -> bool {
+        self.programmatic_setters
+    }
+    pub fn track_presence(&self) -> bool {
+        self.track_presence
+    }
+    pub fn getters(&self) -> bool {
+        self.getters
+    }
+    pub fn path_sep(&self) -> char {
+        match self.path_sep {
+            None => '.',
+            Some(ref sep) => {
+                let mut chars = sep.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(sep), None) if sep.is_ascii() => sep,
+                    _ => panic!("path_sep must be a single ASCII character"),
+                }
+            }
+        }
+    }
     pub fn builder_ident(&self) -> Ident {
         if let Some(ref custom) = self.name {
             return custom.clone();
@@ -374,19 +568,20 @@ impl Options {
     }
     /// Get an iterator over the input struct's fields which pulls fallback
     /// values from struct-level settings.
-    pub fn fields(&self) -> FieldIter {
+    pub fn fields(&self) -> FieldIter<'_> {
         FieldIter(self, self.raw_fields().into_iter())
     }
 
-    pub fn as_from_object(&self) -> FromObject {
+    pub fn as_from_object(&self) -> FromObject<'_> {
         FromObject {
             target_ty: self.ident.clone(),
             generics: Some(&self.generics),
             initializers: Vec::with_capacity(self.field_count()),
             default_struct: self.default.as_ref().map(|x| x.parse_block(false)),
+            record_sources: self.track_presence,
         }
     }
-    pub fn as_builder(&self) -> Builder {
+    pub fn as_builder(&self) -> Builder<'_> {
         Builder {
             ident: self.builder_ident(),
             generics: Some(&self.generics),
@@ -398,19 +593,43 @@ impl Options {
             parser: &self.parser,
             vars: self.vars.clone(),
             pre_source_hook: self.pre_source_hook.clone(),
+            clonable: self.clonable_builder,
+            deprecations: self.has_deprecations(),
+            track_presence: self.track_presence,
         }
     }
-    pub fn as_build_method(&self) -> BuildMethod {
+    pub fn has_deprecations(&self) -> bool {
+        self.raw_fields()
+            .iter()
+            .any(|field| field.deprecated.is_some())
+    }
+    pub fn as_build_method(&self) -> BuildMethod<'_> {
         let (_, ty_generics, _) = self.generics.split_for_impl();
         BuildMethod {
             ident: &self.build_fn.name,
             visibility: self.build_method_vis(),
             target_ty: &self.ident,
             target_ty_generics: Some(ty_generics),
-            initializers: Vec::with_capacity(self.field_count()),
-            doc_comment: None,
-            default_struct: self.default.as_ref().map(|x| x.parse_block(false)),
             validate_fn: self.build_fn.validate.as_ref(),
+            deprecations: self
+                .raw_fields()
+                .into_iter()
+                .filter_map(|field| {
+                    field.deprecated.as_ref().map(|message| {
+                        let check = DeprecationCheck {
+                            lookup_path: &field.get_lookup_key(self.rename_all),
+                            path_sep: self.path_sep(),
+                            message,
+                        };
+                        quote!(#check)
+                    })
+                })
+                .collect(),
+            sources: if self.track_presence {
+                Some(self.as_field_sources())
+            } else {
+                None
+            },
         }
     }
     pub fn as_parser_methods(&self) -> ParserMethods {
@@ -419,7 +638,40 @@ impl Options {
         }
     }
 
-    pub fn as_into_builder(&self) -> IntoBuilder {
+    pub fn as_field_sources(&self) -> FieldSources {
+        FieldSources {
+            path_sep: self.path_sep(),
+            fields: self
+                .fields()
+                .filter_map(|field| {
+                    let name = field.field_ident().to_string();
+                    field
+                        .key_path()
+                        .map(|path| (name.trim_start_matches("r#").to_string(), path))
+                })
+                .collect(),
+        }
+    }
+
+    pub fn as_getters(&self) -> Getters<'_> {
+        Getters {
+            target_ty: &self.ident,
+            generics: &self.generics,
+            fields: self
+                .raw_fields()
+                .into_iter()
+                .map(|field| (field.ident.as_ref().unwrap(), &field.ty))
+                .collect(),
+        }
+    }
+
+    pub fn as_deprecation_methods(&self) -> DeprecationMethods {
+        DeprecationMethods {
+            visibility: self.build_method_vis(),
+        }
+    }
+
+    pub fn as_into_builder(&self) -> IntoBuilder<'_> {
         IntoBuilder {
             ident: self.builder_ident(),
             visibility: self.build_method_vis(),
@@ -466,24 +718,249 @@ impl<'a> FieldWithDefaults<'a> {
             .unwrap_or(Visibility::Inherited)
     }
     pub fn use_parent_default(&self) -> bool {
-        self.field.default.is_none() && self.parent.default.is_some()
+        self.field.default.is_none()
+            && self.field.default_fn.is_none()
+            && self.parent.default.is_some()
     }
     /// Returns an `Initializer` according to the options.
     ///
     /// # Panics
     ///
-    /// if `default_expression` can not be parsed as `Block`.
+    /// if `default_expression` can not be parsed as `Block` or both `default` and `default_fn` are set.
     pub fn as_initializer(&'a self) -> Initializer<'a> {
+        if self.field.default.is_some() && self.field.default_fn.is_some() {
+            panic!(
+                "field {}: default and default_fn are mutually exclusive",
+                self.field_ident()
+            );
+        }
         Initializer {
             field_ident: self.field_ident(),
             default_value: self.field.default.as_ref().map(|x| x.parse_block(false)),
+            default_fn: self.field.default_fn.as_ref(),
             use_default_struct: self.use_parent_default(),
-            lookup_path: self.field.get_lookup_key(),
+            lookup_path: self.field.get_lookup_key(self.parent.rename_all),
+            path_sep: self.parent.path_sep(),
             validation: self.field.validate.clone(),
-            from: self.field.from.clone(),
+            from: self.from(),
             try_from: self.field.try_from.clone(),
             map: self.field.map.clone(),
             from_str: self.field.from_str,
+            parse_with: self.field.parse_with.clone(),
+            flags_from: self.field.flags_from.clone(),
+            split: self.field.split.clone(),
+            flatten: self.flatten(),
+            skip: self.field.skip,
+            record_source: self.parent.track_presence(),
+        }
+    }
+
+    /// Type to read before converting with `From`, `float_from_int` is the same as `from = "f64"`.
+    fn from(&self) -> Option<Path> {
+        if !self.field.float_from_int {
+            return self.field.from.clone();
+        }
+        if self.field.from.is_some() {
+            panic!(
+                "field {}: `float_from_int` can't be combined with `from`",
+                self.field_ident()
+            );
+        }
+        Some(syn::parse_str("f64").unwrap())
+    }
+
+    /// Path of the key this field is read from, unless field is not looked up by a key.
+    pub fn key_path(&self) -> Option<String> {
+        if self.field.skip || self.field.flatten || self.field.rest {
+            return None;
+        }
+        Some(self.field.get_lookup_key(self.parent.rename_all))
+    }
+
+    /// Returns a builder setter, unless field is not looked up by a key or opts out of it.
+    pub fn as_setter(&'a self) -> Option<Setter<'a>> {
+        if self.field.skip_setter {
+            return None;
+        }
+        Some(Setter {
+            visibility: self.parent.build_method_vis(),
+            field_ident: self.field_ident(),
+            ty: &self.field.ty,
+            lookup_path: self.key_path()?,
+            path_sep: self.parent.path_sep(),
+        })
+    }
+
+    /// How this field is flattened, if it is.
+    ///
+    /// `rest` captures keys not used by named fields, `flatten` reads the field from the same object.
+    fn flatten(&self) -> Option<Flatten> {
+        if !(self.field.flatten || self.field.rest) {
+            return None;
+        }
+        if self.field.path.is_some() {
+            panic!(
+                "field {}: flatten, rest and path are mutually exclusive",
+                self.field_ident()
+            );
+        }
+        if self.field.flatten && self.field.rest {
+            panic!(
+                "field {}: flatten and rest are mutually exclusive",
+                self.field_ident()
+            );
+        }
+        if self.field.flatten {
+            return Some(Flatten::Object);
+        }
+        let keys = self
+            .parent
+            .raw_fields()
+            .into_iter()
+            .filter(|field| !field.flatten && !field.rest && !field.skip)
+            .map(|field| {
+                let key = field.get_lookup_key(self.parent.rename_all);
+                key.split(self.parent.path_sep())
+                    .next()
+                    .unwrap_or_default()
+                    .to_string()
+            })
+            .collect();
+        Some(Flatten::Map(keys))
+    }
+}
+
+/// How field and variant names are converted into keys when not set explicitly.
+#[derive(Debug, Clone, Copy)]
+pub enum RenameRule {
+    Lowercase,
+    Uppercase,
+    SnakeCase,
+    KebabCase,
+    CamelCase,
+    PascalCase,
+    ScreamingSnakeCase,
+}
+
+impl RenameRule {
+    /// Convert `PascalCase` variant name.
+    pub fn apply_to_variant(self, variant: &str) -> String {
+        match self {
+            RenameRule::Lowercase => variant.to_lowercase(),
+            RenameRule::Uppercase => variant.to_uppercase(),
+            RenameRule::PascalCase => variant.to_string(),
+            RenameRule::CamelCase => {
+                let mut chars = variant.chars();
+                match chars.next() {
+                    Some(first) => first.to_lowercase().chain(chars).collect(),
+                    None => String::new(),
+                }
+            }
+            RenameRule::SnakeCase | RenameRule::KebabCase | RenameRule::ScreamingSnakeCase => {
+                self.apply_to_field(&Self::separate(variant))
+            }
+        }
+    }
+
+    /// Convert `snake_case` field name.
+    pub fn apply_to_field(self, field: &str) -> String {
+        match self {
+            RenameRule::Lowercase | RenameRule::SnakeCase => field.to_string(),
+            RenameRule::Uppercase | RenameRule::ScreamingSnakeCase => field.to_uppercase(),
+            RenameRule::KebabCase => field.replace('_', "-"),
+            RenameRule::CamelCase | RenameRule::PascalCase => {
+                let mut result = String::with_capacity(field.len());
+                let mut capitalize = matches!(self, RenameRule::PascalCase);
+                for ch in field.chars() {
+                    if ch == '_' {
+                        capitalize = true;
+                    } else if capitalize {
+                        result.extend(ch.to_uppercase());
+                        capitalize = false;
+                    } else {
+                        result.push(ch);
+                    }
+                }
+                result
+            }
+        }
+    }
+
+    /// `FooBar` into `foo_bar`.
+    fn separate(variant: &str) -> String {
+        let mut result = String::with_capacity(variant.len() + 4);
+        for (idx, ch) in variant.char_indices() {
+            if ch.is_uppercase() && idx > 0 {
+                result.push('_');
+            }
+            result.extend(ch.to_lowercase());
+        }
+        result
+    }
+}
+
+impl darling::FromMeta for RenameRule {
+    fn from_string(value: &str) -> darling::Result<Self> {
+        match value {
+            "lowercase" => Ok(RenameRule::Lowercase),
+            "UPPERCASE" => Ok(RenameRule::Uppercase),
+            "snake_case" => Ok(RenameRule::SnakeCase),
+            "kebab-case" => Ok(RenameRule::KebabCase),
+            "camelCase" => Ok(RenameRule::CamelCase),
+            "PascalCase" => Ok(RenameRule::PascalCase),
+            "SCREAMING_SNAKE_CASE" => Ok(RenameRule::ScreamingSnakeCase),
+            _ => Err(darling::Error::unknown_value(value)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromDeriveInput)]
+#[darling(attributes(ucl), supports(enum_unit))]
+pub struct EnumOptions {
+    ident: Ident,
+    generics: Generics,
+    /// Rule for variants without explicit `rename`. Variant names are used as is by default.
+    #[darling(default)]
+    rename_all: Option<RenameRule>,
+    data: darling::ast::Data<Variant, darling::util::Ignored>,
+}
+
+/// Data extracted from the variants of the input enum.
+#[derive(Debug, Clone, FromVariant)]
+#[darling(attributes(ucl))]
+pub struct Variant {
+    ident: Ident,
+    #[darling(default)]
+    rename: Option<String>,
+}
+
+impl EnumOptions {
+    pub fn as_from_object(&self) -> EnumFromObject<'_> {
+        let variants = self
+            .data
+            .as_ref()
+            .take_enum()
+            .expect("Only enums supported");
+        EnumFromObject {
+            target_ty: &self.ident,
+            generics: Some(&self.generics),
+            variants: variants
+                .into_iter()
+                .map(|variant| self.as_variant_match(variant))
+                .collect(),
+        }
+    }
+
+    fn as_variant_match<'a>(&'a self, variant: &'a Variant) -> VariantMatch<'a> {
+        let value = match (&variant.rename, self.rename_all) {
+            (Some(rename), _) => rename.clone(),
+            (None, Some(rule)) => rule.apply_to_variant(&variant.ident.to_string()),
+            (None, None) => variant.ident.to_string(),
+        };
+        VariantMatch {
+            target_ty: &self.ident,
+            variant_ident: &variant.ident,
+            value,
         }
     }
 }
@@ -3,6 +3,7 @@ use darling::ToTokens;
 use proc_macro2::{Ident, TokenStream};
 use quote::TokenStreamExt;
 
+#[derive(Default)]
 pub struct ParserField {}
 
 impl ToTokens for ParserField {
@@ -15,12 +16,6 @@ impl ToTokens for ParserField {
     }
 }
 
-impl Default for ParserField {
-    fn default() -> Self {
-        ParserField {}
-    }
-}
-
 #[derive(Debug)]
 pub struct ParserMethods {
     /// Visibility of the build method, e.g. `syn::Visibility::Public`.
@@ -38,6 +33,8 @@ impl ToTokens for ParserMethods {
         let path = bindings::path_ty();
         let box_ty = bindings::box_ty();
         let var_handler_trait = bindings::var_handler_trait();
+        let macro_handler_trait = bindings::macro_handler_trait();
+        let into_iterator = bindings::into_iterator_trait();
         tokens.append_all(quote! (
         /// Add a chunk of text to the parser. String must:
         /// - not have `\0` character;
@@ -62,15 +59,42 @@ impl ToTokens for ParserMethods {
             self.__parser.register_variable(var, value);
             self
         }
+        /// Register every `(var, value)` pair as a variable, same as calling `register_variable` for each of them.
+        ///
+        /// #### Panics
+        /// This function panics if either `var` or `value` has `\0`.
+        #vis fn register_variables<I, K, V>(&mut self, vars: I) -> &mut Self
+        where
+            I: #into_iterator<Item = (K, V)>,
+            K: #as_ref<str>,
+            V: #as_ref<str>,
+        {
+            for (var, value) in vars {
+                self.register_variable(var, value);
+            }
+            self
+        }
         /// A safe counterpart of [`Parser::set_variable_handler_raw`](#method.set_variables_handler_raw). Unlike unsafe version this one takes ownership of a handler and ensures it stays alive as long as parser does.
         ///
         /// ### Caveats
         ///
         /// Parser can have only bar handler. In order to have multiple, please use [`CompoundHandler`](../../variable_handlers/compound/struct.CompoundHandler.html) to join multiple handlers into one.
+        ///
+        /// Handlers are not carried over when a clonable builder is cloned, use `pre_source_hook` for that.
         #vis fn set_variables_handler(&mut self, handler: #box_ty<dyn #var_handler_trait>) -> &mut Self {
             self.__parser.set_variables_handler(handler);
             self
         }
+        /// Register a handler for macro `.name`. Parser takes ownership of a handler and ensures it stays alive as long as parser does.
+        ///
+        /// Handlers are not carried over when a clonable builder is cloned, use `parser(register_macro(..))` for that.
+        ///
+        /// #### Panics
+        /// This function panics if `name` has `\0`.
+        #vis fn register_macro<N: #as_ref<str>>(&mut self, name: N, handler: #box_ty<dyn #macro_handler_trait>) -> &mut Self {
+            self.__parser.register_macro(name, handler);
+            self
+        }
         /// Add the standard file variables to the `parser` based on the `filename` specified:
         ///
         /// - `$FILENAME`- a filename of ucl input
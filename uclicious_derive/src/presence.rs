@@ -0,0 +1,78 @@
+use crate::bindings;
+use darling::ToTokens;
+use proc_macro2::{Ident, TokenStream};
+use quote::TokenStreamExt;
+
+/// Builder field holding values that `env_path` includes set for fields, together with names of those fields.
+pub struct EnvSourcesField;
+
+impl ToTokens for EnvSourcesField {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let ident: Ident = syn::parse_str("__env_sources").unwrap();
+        let vec_ty = bindings::vec_ty();
+        let obj_ty = bindings::ucl_object_ty();
+        tokens.append_all(quote!(
+            #ident: #vec_ty<(&'static str, #obj_ty)>,
+        ));
+    }
+}
+
+/// Checks run by the build method that mark fields still holding values set by `env_path` includes as `FieldSource::Env`.
+#[derive(Debug)]
+pub struct FieldSources {
+    /// Separator of segments in paths.
+    pub path_sep: char,
+    /// Name of every field that is looked up by a key together with the path of the key.
+    pub fields: Vec<(String, String)>,
+}
+
+impl ToTokens for FieldSources {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let field_source = bindings::field_source_ty();
+        let path_sep = self.path_sep;
+        for (name, lookup_path) in self.fields.iter() {
+            tokens.append_all(quote!(
+                if sources.get(#name) == Some(&#field_source::Config) {
+                    if let Some(value) = root.lookup_path_sep(#lookup_path, #path_sep) {
+                        let from_env = self
+                            .__env_sources
+                            .iter()
+                            .any(|(field, env)| *field == #name && env.as_ptr() == value.as_ptr());
+                        if from_env {
+                            sources.insert(#name, #field_source::Env);
+                        }
+                    }
+                }
+            ));
+        }
+    }
+}
+
+/// Builder method returning values that a parser currently has for every field looked up by a key. `env_path` includes compare them before and after the file is added.
+pub struct TrackedValuesMethod(pub FieldSources);
+
+impl ToTokens for TrackedValuesMethod {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let parser_ty = bindings::ucl_parser();
+        let vec_ty = bindings::vec_ty();
+        let obj_ty = bindings::ucl_object_ty();
+        let to_owned = bindings::to_owned_trait();
+        let path_sep = self.0.path_sep;
+        let lookups = self.0.fields.iter().map(|(name, lookup_path)| {
+            quote!(
+                if let Some(value) = root.lookup_path_sep(#lookup_path, #path_sep) {
+                    values.push((#name, #to_owned::to_owned(&value)));
+                }
+            )
+        });
+        tokens.append_all(quote!(
+            fn __tracked_values(parser: &#parser_ty) -> #vec_ty<(&'static str, #obj_ty)> {
+                let mut values = #vec_ty::new();
+                if let Ok(root) = parser.get_object() {
+                    #(#lookups)*
+                }
+                values
+            }
+        ));
+    }
+}
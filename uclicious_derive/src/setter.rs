@@ -0,0 +1,63 @@
+use crate::bindings;
+use darling::ToTokens;
+use proc_macro2::{Span, TokenStream};
+use quote::TokenStreamExt;
+
+/// Builder method that sets a field by replacing its key in the parsed object, the new value gets the highest priority.
+#[derive(Debug)]
+pub struct Setter<'a> {
+    /// Visibility of the setter, e.g. `syn::Visibility::Public`.
+    pub visibility: syn::Visibility,
+    /// Name of the target field.
+    pub field_ident: &'a syn::Ident,
+    /// Type of the target field.
+    pub ty: &'a syn::Type,
+    /// Path of the key.
+    pub lookup_path: String,
+    /// Separator of segments in `lookup_path`.
+    pub path_sep: char,
+}
+
+impl<'a> ToTokens for Setter<'a> {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let vis = &self.visibility;
+        let ty = self.ty;
+        let ident = syn::Ident::new(&format!("with_{}", self.field_ident), Span::call_site());
+        let doc = format!(
+            "Set `{}` to the given value, it takes precedence over any parsed value.",
+            self.field_ident
+        );
+        let segments: Vec<&str> = self.lookup_path.split(self.path_sep).collect();
+        let (last, parents) = segments.split_last().expect("Empty key path");
+        let result = bindings::result_ty();
+        let err = bindings::ucl_parser_error();
+        let obj_ty = bindings::ucl_object_ty();
+        let into_trait = bindings::into_trait();
+        let to_owned_trait = bindings::to_owned_trait();
+        let priority = bindings::ucilicous_priority_type();
+        let strategy = bindings::ucl_default_strategy();
+        tokens.append_all(quote!(
+            #[doc = #doc]
+            #vis fn #ident(mut self, value: #ty) -> #result<Self, #err> {
+                let mut object: #obj_ty = #into_trait::into(value);
+                object.set_priority(#priority::new(15));
+                // Empty chunk only creates the top object if nothing was added yet.
+                self.__parser.add_chunk_full("", #priority::default(), #strategy)?;
+                let mut parent = self.__parser.get_object()?;
+                let parents: &[&str] = &[#(#parents),*];
+                for key in parents {
+                    parent = match parent.lookup(key) {
+                        Some(child) if child.is_object() => #to_owned_trait::to_owned(&child),
+                        _ => {
+                            let child = #obj_ty::new_object();
+                            parent.replace(key, child.clone());
+                            child
+                        }
+                    };
+                }
+                parent.replace(#last, object);
+                Ok(self)
+            }
+        ));
+    }
+}
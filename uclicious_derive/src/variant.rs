@@ -0,0 +1,84 @@
+use crate::bindings;
+use proc_macro2::TokenStream;
+use quote::{ToTokens, TokenStreamExt};
+
+/// Match arm that maps a string onto a unit variant.
+#[derive(Debug, Clone)]
+pub struct VariantMatch<'a> {
+    /// Type of the target enum.
+    pub target_ty: &'a syn::Ident,
+    /// Name of the target variant.
+    pub variant_ident: &'a syn::Ident,
+    /// String that selects this variant.
+    pub value: String,
+}
+
+impl<'a> ToTokens for VariantMatch<'a> {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let target_ty = self.target_ty;
+        let variant_ident = self.variant_ident;
+        let value = &self.value;
+        tokens.append_all(quote!(
+            #value => Ok(#target_ty::#variant_ident),
+        ));
+    }
+}
+
+/// `FromObject` implementation for fieldless enums. Objects are read as strings.
+pub struct EnumFromObject<'a> {
+    /// Type of the target
+    pub target_ty: &'a syn::Ident,
+    /// Type parameters and lifetimes attached to target type
+    pub generics: Option<&'a syn::Generics>,
+    /// Match arms for every variant.
+    pub variants: Vec<VariantMatch<'a>>,
+}
+
+impl<'a> ToTokens for EnumFromObject<'a> {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let target_ty = self.target_ty;
+        let target_ty_generics = &self.generics;
+        let variants = &self.variants;
+        let expected = self
+            .variants
+            .iter()
+            .map(|variant| format!("`{}`", variant.value))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let result = bindings::result_ty();
+        let error_ty = bindings::ucl_object_error();
+        let try_from = bindings::from_object_trait();
+        let obj_ref_ty = bindings::ucl_object_ref_ty();
+        let obj_ty = bindings::ucl_object_ty();
+        let string_ty = bindings::string_ty();
+        let borrow = bindings::borrow_trait();
+
+        tokens.append_all(quote!(
+            impl #try_from<#obj_ref_ty> for #target_ty #target_ty_generics {
+                fn try_from(source: #obj_ref_ty) -> #result<Self, #error_ty> {
+                    let value: #string_ty = #try_from::try_from(source)?;
+                    match value.as_str() {
+                        #(#variants)*
+                        other => Err(#error_ty::other(format!(
+                            "unknown variant `{}`, expected one of {}",
+                            other, #expected
+                        ))),
+                    }
+                }
+            }
+            impl #try_from<&#obj_ref_ty> for #target_ty #target_ty_generics {
+                fn try_from(root: &#obj_ref_ty) -> #result<Self, #error_ty> {
+                    let (_, obj) = root.entry();
+                    #try_from::try_from(obj)
+                }
+            }
+            impl #try_from<#obj_ty> for #target_ty #target_ty_generics {
+                fn try_from(source: #obj_ty) -> #result<Self, #error_ty> {
+                    let obj: &#obj_ref_ty = #borrow::borrow(&source);
+                    #try_from::try_from(obj)
+                }
+            }
+        ))
+    }
+}